@@ -5,10 +5,12 @@ use hotshot_types::{
     data::{fake_commitment, BlockError, Leaf, ViewNumber},
     traits::{
         block_contents::BlockHeader,
-        node_implementation::NodeType,
+        election::{Membership, StakeTableUpdate},
+        node_implementation::{ConsensusTime, NodeType},
         states::{InstanceState, TestableState, ValidatedState},
         BlockPayload,
     },
+    ValidatorConfig,
 };
 
 use serde::{Deserialize, Serialize};
@@ -30,14 +32,35 @@ pub struct TestValidatedState {
     block_height: u64,
     /// the previous state commitment
     prev_state_commitment: Commitment<Self>,
+    /// the stake table update made by the block, as the first view the new committee is active
+    /// in and the size of the new committee
+    stake_table_update: Option<(u64, u64)>,
+}
+
+impl TestValidatedState {
+    /// Make the block behind this state update the stake table, electing a committee of
+    /// `num_nodes` test nodes that is active from `first_view` on.
+    #[must_use]
+    pub fn with_stake_table_update(self, first_view: u64, num_nodes: u64) -> Self {
+        Self {
+            stake_table_update: Some((first_view, num_nodes)),
+            ..self
+        }
+    }
 }
 
 impl Committable for TestValidatedState {
     fn commit(&self) -> Commitment<Self> {
-        commit::RawCommitmentBuilder::new("Test State Commit")
+        let builder = commit::RawCommitmentBuilder::new("Test State Commit")
             .u64_field("block_height", self.block_height)
-            .field("prev_state_commitment", self.prev_state_commitment)
-            .finalize()
+            .field("prev_state_commitment", self.prev_state_commitment);
+        match self.stake_table_update {
+            Some((first_view, num_nodes)) => builder
+                .u64_field("stake_table_update_first_view", first_view)
+                .u64_field("stake_table_update_num_nodes", num_nodes)
+                .finalize(),
+            None => builder.finalize(),
+        }
     }
 
     fn tag() -> String {
@@ -50,6 +73,7 @@ impl Default for TestValidatedState {
         Self {
             block_height: 0,
             prev_state_commitment: fake_commitment(),
+            stake_table_update: None,
         }
     }
 }
@@ -70,6 +94,7 @@ impl<TYPES: NodeType> ValidatedState<TYPES> for TestValidatedState {
         Ok(TestValidatedState {
             block_height: self.block_height + 1,
             prev_state_commitment: self.commit(),
+            stake_table_update: None,
         })
     }
 
@@ -82,6 +107,25 @@ impl<TYPES: NodeType> ValidatedState<TYPES> for TestValidatedState {
 
     fn on_commit(&self) {}
 
+    fn stake_table_update(&self) -> Option<StakeTableUpdate<TYPES>> {
+        let (first_view, num_nodes) = self.stake_table_update?;
+        let committee = (0..num_nodes)
+            .map(|node_id| {
+                ValidatorConfig::<TYPES::SignatureKey>::generated_from_seed_indexed(
+                    [0u8; 32], node_id, 1,
+                )
+                .get_public_config()
+            })
+            .collect();
+        Some(StakeTableUpdate {
+            first_view: TYPES::Time::new(first_view),
+            membership: TYPES::Membership::create_election(
+                committee,
+                TYPES::Membership::default_election_config(num_nodes),
+            ),
+        })
+    }
+
     fn genesis(_instance: &Self::Instance) -> Self {
         Self::default()
     }
@@ -9,7 +9,7 @@ use hotshot::traits::implementations::{CombinedNetworks, UnderlyingCombinedNetwo
 use hotshot::{
     traits::{
         implementations::{Libp2pNetwork, MemoryStorage, NetworkingMetricsValue, WebServerNetwork},
-        NodeImplementation,
+        NoPayments, NodeImplementation,
     },
     types::{SignatureKey, SystemContextHandle},
     Memberships, Networks, SystemContext,
@@ -61,6 +61,13 @@ use std::fmt::Debug;
 use std::{fs, time::Instant};
 use tracing::{error, info, warn};
 
+/// Counts allocations on the consensus hot paths, see `hotshot_utils::alloc_tracking`.
+/// The counts are process-wide, so they are only meaningful for binaries running a single node.
+#[cfg(feature = "alloc-tracking")]
+#[global_allocator]
+static ALLOCATOR: hotshot_utils::alloc_tracking::CountingAllocator =
+    hotshot_utils::alloc_tracking::CountingAllocator;
+
 #[derive(Parser, Debug, Clone)]
 #[command(
     name = "Multi-machine consensus",
@@ -72,6 +79,13 @@ pub struct OrchestratorArgs {
     pub url: Url,
     /// The configuration file to be used for this run
     pub config_file: String,
+    /// The orchestrator's secret, from which it derives the key it signs configs with.
+    /// Generated fresh for this run if neither this nor `--orchestrator-secret-file` is given.
+    #[arg(long, env = "ORCHESTRATOR_SECRET", hide_env_values = true)]
+    pub orchestrator_secret: Option<String>,
+    /// A file holding the orchestrator's secret
+    #[arg(long, conflicts_with = "orchestrator_secret")]
+    pub orchestrator_secret_file: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -120,20 +134,40 @@ pub fn load_config_from_file<TYPES: NodeType>(
 }
 
 /// Runs the orchestrator
+/// # Panics
+/// if the orchestrator secret can't be read or is invalid
 pub async fn run_orchestrator<
     TYPES: NodeType,
     DACHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey> + Debug,
     QUORUMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey> + Debug,
     NODE: NodeImplementation<TYPES, Storage = MemoryStorage<TYPES>>,
 >(
-    OrchestratorArgs { url, config_file }: OrchestratorArgs,
+    OrchestratorArgs {
+        url,
+        config_file,
+        orchestrator_secret,
+        orchestrator_secret_file,
+    }: OrchestratorArgs,
 ) {
     error!("Starting orchestrator",);
     let run_config = load_config_from_file::<TYPES>(&config_file);
+    let orchestrator_secret = match (orchestrator_secret, orchestrator_secret_file) {
+        (Some(secret), _) => secret,
+        (None, Some(secret_file)) => fs::read_to_string(&secret_file).unwrap_or_else(|_| {
+            panic!("Could not read orchestrator secret file located at {secret_file}")
+        }),
+        (None, None) => {
+            warn!("No orchestrator secret given, generating a fresh key for this run");
+            hotshot_orchestrator::generate_orchestrator_secret()
+        }
+    };
+    let (_, private_key) =
+        hotshot_orchestrator::orchestrator_key_pair::<TYPES::SignatureKey>(&orchestrator_secret)
+            .expect("Invalid orchestrator secret");
     let _result = hotshot_orchestrator::run_orchestrator::<
         TYPES::SignatureKey,
         TYPES::ElectionConfigType,
-    >(run_config, url)
+    >(run_config, url, private_key)
     .await;
 }
 
@@ -376,6 +410,7 @@ pub trait RunDA<
             networks_bundle,
             initializer,
             ConsensusMetricsValue::default(),
+            Arc::new(NoPayments),
         )
         .await
         .expect("Could not init hotshot")
@@ -10,6 +10,9 @@ use clap::Parser;
 use hotshot_example_types::state_types::TestTypes;
 use hotshot_orchestrator::client::ValidatorArgs;
 use hotshot_orchestrator::config::NetworkConfig;
+use hotshot_orchestrator::{
+    encode_orchestrator_key, generate_orchestrator_secret, orchestrator_key_pair,
+};
 use hotshot_types::traits::node_implementation::NodeType;
 use std::net::{IpAddr, Ipv4Addr};
 use surf_disco::Url;
@@ -39,7 +42,13 @@ async fn main() {
     let args = ConfigArgs::parse();
     let orchestrator_url = Url::parse("http://localhost:4444").unwrap();
 
-    // orchestrator
+    // orchestrator, with a key generated for this run
+    let orchestrator_secret = generate_orchestrator_secret();
+    let orchestrator_key = encode_orchestrator_key(
+        orchestrator_key_pair::<<TestTypes as NodeType>::SignatureKey>(&orchestrator_secret)
+            .expect("freshly generated orchestrator secret is valid")
+            .0,
+    );
     async_spawn(run_orchestrator::<
         TestTypes,
         DANetwork,
@@ -48,6 +57,8 @@ async fn main() {
     >(OrchestratorArgs {
         url: orchestrator_url.clone(),
         config_file: args.config_file.clone(),
+        orchestrator_secret: Some(orchestrator_secret),
+        orchestrator_secret_file: None,
     }));
 
     // nodes
@@ -58,12 +69,14 @@ async fn main() {
     let mut nodes = Vec::new();
     for _ in 0..config.config.total_nodes.into() {
         let orchestrator_url = orchestrator_url.clone();
+        let orchestrator_key = orchestrator_key.clone();
         let node = async_spawn(async move {
             infra::main_entry_point::<TestTypes, DANetwork, QuorumNetwork, NodeImpl, ThisRun>(
                 ValidatorArgs {
                     url: orchestrator_url,
                     public_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
                     network_config_file: None,
+                    orchestrator_key,
                 },
             )
             .await;
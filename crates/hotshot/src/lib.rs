@@ -44,6 +44,7 @@ use hotshot_types::{
         election::Membership,
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, NodeType},
+        payments::BuilderPayments,
         signature_key::SignatureKey,
         states::ValidatedState,
         storage::StoredView,
@@ -135,6 +136,9 @@ pub struct SystemContext<TYPES: NodeType, I: NodeImplementation<TYPES>> {
     /// Memberships used by consensus
     pub memberships: Arc<Memberships<TYPES>>,
 
+    /// Payments for the block space namespaces use, consulted by the block builder
+    pub payments: Arc<dyn BuilderPayments<TYPES>>,
+
     /// the metrics that the implementor is using.
     _metrics: Arc<ConsensusMetricsValue>,
 
@@ -161,7 +165,15 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
     /// To do a full initialization, use `fn init` instead, which will set up background tasks as
     /// well.
     #[allow(clippy::too_many_arguments)]
-    #[instrument(skip(private_key, storage, memberships, networks, initializer, metrics))]
+    #[instrument(skip(
+        private_key,
+        storage,
+        memberships,
+        networks,
+        initializer,
+        metrics,
+        payments
+    ))]
     pub async fn new(
         public_key: TYPES::SignatureKey,
         private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
@@ -172,6 +184,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
         networks: Networks<TYPES, I>,
         initializer: HotShotInitializer<TYPES>,
         metrics: ConsensusMetricsValue,
+        payments: Arc<dyn BuilderPayments<TYPES>>,
     ) -> Result<Arc<Self>, HotShotError<TYPES>> {
         debug!("Creating a new hotshot");
 
@@ -258,6 +271,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             storage,
             networks: Arc::new(networks),
             memberships: Arc::new(memberships),
+            payments,
             _metrics: consensus_metrics.clone(),
             internal_event_stream: (internal_tx, internal_rx.deactivate()),
             output_event_stream: (external_tx, external_rx.deactivate()),
@@ -406,6 +420,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
         networks: Networks<TYPES, I>,
         initializer: HotShotInitializer<TYPES>,
         metrics: ConsensusMetricsValue,
+        payments: Arc<dyn BuilderPayments<TYPES>>,
     ) -> Result<
         (
             SystemContextHandle<TYPES, I>,
@@ -425,6 +440,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             networks,
             initializer,
             metrics,
+            payments,
         )
         .await?;
         let handle = hotshot.clone().run_tasks().await;
@@ -459,6 +475,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
 
         let quorum_network = self.networks.quorum_network.clone();
         let da_network = self.networks.da_network.clone();
+        // The network event tasks route messages with the same memberships as the tasks they
+        // carry messages for. Decided stake table updates only change the peers of the quorum
+        // network, which its event task tracks.
         let quorum_membership = self.memberships.quorum_membership.clone();
         let da_membership = self.memberships.da_membership.clone();
         let vid_membership = self.memberships.vid_membership.clone();
@@ -483,6 +502,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             event_rx.activate_cloned(),
             quorum_network.clone(),
             quorum_membership,
+            true,
             network::quorum_filter,
         )
         .await;
@@ -492,6 +512,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             event_rx.activate_cloned(),
             da_network.clone(),
             da_membership,
+            false,
             network::committee_filter,
         )
         .await;
@@ -501,6 +522,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             event_rx.activate_cloned(),
             quorum_network.clone(),
             view_sync_membership,
+            false,
             network::view_sync_filter,
         )
         .await;
@@ -510,6 +532,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> SystemContext<TYPES, I> {
             event_rx.activate_cloned(),
             quorum_network.clone(),
             vid_membership,
+            false,
             network::vid_filter,
         )
         .await;
@@ -583,6 +606,10 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> ConsensusApi<TYPES, I>
         self.hotshot.config.min_transactions
     }
 
+    fn inline_payload_threshold(&self) -> usize {
+        self.hotshot.config.inline_payload_threshold
+    }
+
     async fn send_event(&self, event: Event<TYPES>) {
         debug!(?event, "send_event");
         broadcast_event(event, &self.hotshot.output_event_stream.0).await;
@@ -23,7 +23,10 @@ use hotshot_task_impls::{
 };
 use hotshot_types::{
     message::Message,
-    traits::{election::Membership, network::ConnectedNetwork},
+    traits::{
+        election::{Membership, ViewMemberships},
+        network::ConnectedNetwork,
+    },
 };
 use hotshot_types::{
     message::Messages,
@@ -85,6 +88,10 @@ pub async fn add_network_message_task<
     task_reg.register(handle).await;
 }
 /// Add the network task to handle events and send messages.
+///
+/// Messages are routed with `membership`, which should be the membership consensus resolves
+/// leaders with. Of the tasks sharing `channel`, exactly one should `track_stake_table`, recording
+/// decided stake table updates and connecting `channel` to the validators that joined.
 pub async fn add_network_event_task<
     TYPES: NodeType,
     NET: ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey>,
@@ -94,12 +101,14 @@ pub async fn add_network_event_task<
     rx: Receiver<HotShotEvent<TYPES>>,
     channel: Arc<NET>,
     membership: TYPES::Membership,
+    track_stake_table: bool,
     filter: fn(&HotShotEvent<TYPES>) -> bool,
 ) {
     let network_state: NetworkEventTaskState<_, _> = NetworkEventTaskState {
         channel,
         view: TYPES::Time::genesis(),
-        membership,
+        membership: membership.clone(),
+        stake_table: track_stake_table.then(|| ViewMemberships::new(membership)),
         filter,
     };
     let task = Task::new(tx, rx, task_reg.clone(), network_state);
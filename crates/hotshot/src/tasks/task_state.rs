@@ -10,6 +10,7 @@ use hotshot_task_impls::{
     vid::VIDTaskState,
     view_sync::ViewSyncTaskState,
 };
+use hotshot_types::data::PayloadAvailability;
 use hotshot_types::traits::election::Membership;
 use hotshot_types::traits::{
     block_contents::vid_commitment,
@@ -147,6 +148,8 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> CreateTaskState<TYPES, I>
             cur_view: handle.get_cur_view().await,
             network: handle.hotshot.networks.quorum_network.clone(),
             membership: handle.hotshot.memberships.quorum_membership.clone().into(),
+            payments: handle.hotshot.payments.clone(),
+            pending_usage: BTreeMap::new(),
             public_key: handle.public_key().clone(),
             private_key: handle.private_key().clone(),
             id: handle.hotshot.id,
@@ -176,6 +179,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> CreateTaskState<TYPES, I>
             payload_commitment_and_metadata: Some(CommitmentAndMetadata {
                 commitment: payload_commitment,
                 metadata,
+                payload_availability: PayloadAvailability::Reference,
                 is_genesis: true,
             }),
             api: handle.clone(),
@@ -2,11 +2,13 @@
 pub mod election;
 mod networking;
 mod node_implementation;
+mod payments;
 mod storage;
 
 pub use hotshot_types::traits::{BlockPayload, ValidatedState};
 pub use networking::{NetworkError, NetworkReliability};
 pub use node_implementation::{NodeImplementation, TestableNodeImplementation};
+pub use payments::{BuilderPayments, NoPayments};
 pub use storage::{Result as StorageResult, Storage};
 
 /// Module for publicly usable implementations of the traits
@@ -21,6 +23,7 @@ pub mod implementations {
             web_server_network::WebServerNetwork,
             NetworkingMetricsValue,
         },
+        payments::l1_payments::{L1Client, L1Payments},
         storage::memory_storage::MemoryStorage, // atomic_storage::AtomicStorage,
     };
 }
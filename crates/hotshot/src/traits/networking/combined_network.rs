@@ -371,6 +371,20 @@ impl<TYPES: NodeType> ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey>
         <Libp2pNetwork<_, _> as ConnectedNetwork<Message<TYPES>,TYPES::SignatureKey>>::
             inject_consensus_info(self.secondary(), event).await;
     }
+
+    async fn update_peers(&self, peers: BTreeSet<TYPES::SignatureKey>) {
+        <WebServerNetwork<_> as ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey>>::update_peers(
+            self.primary(),
+            peers.clone(),
+        )
+        .await;
+
+        <Libp2pNetwork<_, _> as ConnectedNetwork<Message<TYPES>, TYPES::SignatureKey>>::update_peers(
+            self.secondary(),
+            peers,
+        )
+        .await;
+    }
 }
 
 #[cfg(test)]
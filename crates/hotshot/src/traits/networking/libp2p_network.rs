@@ -34,7 +34,11 @@ use hotshot_types::{
         node_implementation::NodeType,
     },
 };
-use hotshot_utils::{bincode::bincode_opts, version::read_version};
+use hotshot_utils::{
+    alloc_tracking::{AllocationTracker, HotPath},
+    bincode::bincode_opts,
+    version::read_version,
+};
 use libp2p_identity::PeerId;
 #[cfg(feature = "hotshot-testing")]
 use libp2p_networking::network::{MeshParams, NetworkNodeConfigBuilder};
@@ -129,6 +133,9 @@ struct Libp2pNetworkInner<M: NetworkMsg, K: SignatureKey + 'static> {
     /// hash(hashset) -> topic
     /// btreemap ordered so is hashable
     topic_map: RwLock<BiHashMap<BTreeSet<K>, String>>,
+    /// the validators we connect to. Starts out as the quorum committee, and grows as stake
+    /// table updates are decided
+    validators: RwLock<BTreeSet<K>>,
     /// the latest view number (for node lookup purposes)
     /// NOTE: supposed to represent a ViewNumber but we
     /// haven't made that atomic yet and we prefer lock-free
@@ -348,6 +355,8 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
         let mut pubkey_pid_map = BiHashMap::new();
         pubkey_pid_map.insert(pk.clone(), network_handle.peer_id());
 
+        let validators = RwLock::new(committee_pks.clone());
+
         let mut topic_map = BiHashMap::new();
         topic_map.insert(committee_pks, QC_TOPIC.to_string());
         topic_map.insert(da_pks, "DA".to_string());
@@ -376,6 +385,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
                 is_bootstrapped: Arc::new(AtomicBool::new(false)),
                 metrics,
                 topic_map,
+                validators,
                 node_lookup_send,
                 // Start the latest view from 0. "Latest" refers to "most recent view we are polling for
                 // proposals on". We need this because to have consensus info injected we need a working
@@ -523,7 +533,10 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
     ) -> Result<(), NetworkError> {
         match msg {
             GossipMsg(msg) => {
-                let result: Result<M, _> = bincode_opts().deserialize(&msg);
+                let result: Result<M, _> = {
+                    let _receive_allocations = AllocationTracker::start(HotPath::Receive);
+                    bincode_opts().deserialize(&msg)
+                };
                 if let Ok(result) = result {
                     sender
                         .send(result)
@@ -532,9 +545,12 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
                 }
             }
             DirectRequest(msg, _pid, chan) => {
-                let result: Result<M, _> = bincode_opts()
-                    .deserialize(&msg)
-                    .context(FailedToSerializeSnafu);
+                let result: Result<M, _> = {
+                    let _receive_allocations = AllocationTracker::start(HotPath::Receive);
+                    bincode_opts()
+                        .deserialize(&msg)
+                        .context(FailedToSerializeSnafu)
+                };
                 if let Ok(result) = result {
                     sender
                         .send(result)
@@ -702,6 +718,13 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
                 .map_err(|_| NetworkError::ShutDown)?;
         }
 
+        let serialized_msg = {
+            let _broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
+            bincode_opts()
+                .serialize(&message)
+                .context(FailedToSerializeSnafu)?
+        };
+
         // NOTE: metrics is threadsafe, so clone is fine (and lightweight)
         #[cfg(feature = "hotshot-testing")]
         {
@@ -709,9 +732,6 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
             if let Some(ref config) = &self.inner.reliability_config {
                 let handle = self.inner.handle.clone();
 
-                let serialized_msg = bincode_opts()
-                    .serialize(&message)
-                    .context(FailedToSerializeSnafu)?;
                 let fut = config.clone().chaos_send_msg(
                     serialized_msg,
                     Arc::new(move |msg: Vec<u8>| {
@@ -736,7 +756,12 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
             }
         }
 
-        match self.inner.handle.gossip(topic, &message).await {
+        match self
+            .inner
+            .handle
+            .gossip_no_serialize(topic, serialized_msg)
+            .await
+        {
             Ok(()) => {
                 self.inner.metrics.outgoing_broadcast_message_count.add(1);
                 Ok(())
@@ -806,15 +831,19 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
             }
         };
 
+        let serialized_msg = {
+            let _broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
+            bincode_opts()
+                .serialize(&message)
+                .context(FailedToSerializeSnafu)?
+        };
+
         #[cfg(feature = "hotshot-testing")]
         {
             let metrics = self.inner.metrics.clone();
             if let Some(ref config) = &self.inner.reliability_config {
                 let handle = self.inner.handle.clone();
 
-                let serialized_msg = bincode_opts()
-                    .serialize(&message)
-                    .context(FailedToSerializeSnafu)?;
                 let fut = config.clone().chaos_send_msg(
                     serialized_msg,
                     Arc::new(move |msg: Vec<u8>| {
@@ -838,7 +867,12 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
             }
         }
 
-        match self.inner.handle.direct_request(pid, &message).await {
+        match self
+            .inner
+            .handle
+            .direct_request_no_serialize(pid, serialized_msg)
+            .await
+        {
             Ok(()) => Ok(()),
             Err(e) => Err(e.into()),
         }
@@ -895,4 +929,42 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Libp2p
             _ => {}
         }
     }
+
+    #[instrument(name = "Libp2pNetwork::update_peers", skip_all)]
+    async fn update_peers(&self, peers: BTreeSet<K>) {
+        let mut validators = self.inner.validators.write().await;
+        let joined: Vec<K> = peers
+            .difference(&validators)
+            .filter(|key| **key != self.inner.pk)
+            .cloned()
+            .collect();
+        if joined.is_empty() {
+            return;
+        }
+        // Validators which left the stake table are kept: consensus still elects them as leaders
+        // and sends them votes until its own membership changes.
+        validators.extend(peers);
+        drop(validators);
+        info!("Dialling {} validators which joined", joined.len());
+
+        let handle = self.inner.handle.clone();
+        let dht_timeout = self.inner.dht_timeout;
+        // DHT lookups can take up to `dht_timeout`, so don't hold up the caller
+        async_spawn(async move {
+            let handle = &handle;
+            // looking up the peer id of a joining validator in kademlia makes its addresses
+            // known, so we connect to it ahead of its first view
+            join_all(joined.into_iter().map(|key| async move {
+                match handle.lookup_node::<K>(key.clone(), dht_timeout).await {
+                    Ok(pid) => {
+                        if let Err(err) = handle.lookup_pid(pid).await {
+                            warn!("Failed to dial joining validator {:?}: {}", key, err);
+                        }
+                    }
+                    Err(err) => warn!("Failed to look up joining validator {:?}: {}", key, err),
+                }
+            }))
+            .await;
+        });
+    }
 }
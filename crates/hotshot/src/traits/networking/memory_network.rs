@@ -23,7 +23,10 @@ use hotshot_types::{
     },
     BoxSyncFuture,
 };
-use hotshot_utils::bincode::bincode_opts;
+use hotshot_utils::{
+    alloc_tracking::{AllocationTracker, HotPath},
+    bincode::bincode_opts,
+};
 use rand::Rng;
 use snafu::ResultExt;
 use std::{
@@ -124,7 +127,10 @@ impl<M: NetworkMsg, K: SignatureKey> MemoryNetwork<M, K> {
                 while let Some(vec) = task_stream.next().await {
                     trace!(?vec, "Incoming message");
                     // Attempt to decode message
-                    let x = bincode_opts().deserialize(&vec);
+                    let x = {
+                        let _receive_allocations = AllocationTracker::start(HotPath::Receive);
+                        bincode_opts().deserialize(&vec)
+                    };
                     match x {
                         Ok(x) => {
                             let ts = task_send.clone();
@@ -247,9 +253,12 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Memory
     ) -> Result<(), NetworkError> {
         trace!(?message, "Broadcasting message");
         // Bincode the message
-        let vec = bincode_opts()
-            .serialize(&message)
-            .context(FailedToSerializeSnafu)?;
+        let vec = {
+            let _broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
+            bincode_opts()
+                .serialize(&message)
+                .context(FailedToSerializeSnafu)?
+        };
         trace!("Message bincoded, sending");
         for node in &self.inner.master_map.map {
             // TODO delay/drop etc here
@@ -304,9 +313,12 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, K> for Memory
     async fn direct_message(&self, message: M, recipient: K) -> Result<(), NetworkError> {
         // debug!(?message, ?recipient, "Sending direct message");
         // Bincode the message
-        let vec = bincode_opts()
-            .serialize(&message)
-            .context(FailedToSerializeSnafu)?;
+        let vec = {
+            let _broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
+            bincode_opts()
+                .serialize(&message)
+                .context(FailedToSerializeSnafu)?
+        };
         trace!("Message bincoded, finding recipient");
         if let Some(node) = self.inner.master_map.map.get(&recipient) {
             let node = node.value().clone();
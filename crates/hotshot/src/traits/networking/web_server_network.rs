@@ -26,7 +26,10 @@ use hotshot_types::{
     },
     BoxSyncFuture,
 };
-use hotshot_utils::version::read_version;
+use hotshot_utils::{
+    alloc_tracking::{AllocationTracker, HotPath},
+    version::read_version,
+};
 use hotshot_web_server::{self, config};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
@@ -60,6 +63,87 @@ fn hash<T: Hash>(t: &T) -> u64 {
     s.finish()
 }
 
+/// Maximum number of responses kept in the client-side response cache
+const RESPONSE_CACHE_ENTRIES: usize = 500;
+
+/// Maximum total size in bytes of the responses kept in the client-side response cache
+const RESPONSE_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Bounded cache of web server responses that can no longer change once they exist.
+///
+/// Proposals, certificates and VID shares for a view are only ever posted once, so after we have
+/// fetched them there is no reason to ask the web server again when we poll for the view again,
+/// e.g. when consensus retries, even after the web server has dropped the view.
+/// Entries are keyed by view and message purpose, and evicted least recently used first once
+/// either the entry or the byte bound is hit.
+#[derive(Debug)]
+struct ResponseCache {
+    /// Cached responses, keyed by view number and message purpose
+    entries: LruCache<(u64, MessagePurpose), Vec<Vec<u8>>>,
+    /// Total size in bytes of the cached responses
+    bytes: usize,
+    /// Upper bound on `bytes`
+    max_bytes: usize,
+}
+
+impl ResponseCache {
+    /// Create an empty cache holding at most `max_entries` responses and `max_bytes` bytes
+    fn new(max_entries: NonZeroUsize, max_bytes: usize) -> Self {
+        Self {
+            entries: LruCache::new(max_entries),
+            bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Whether responses for `message_purpose` are immutable, and so safe to cache
+    fn is_cacheable(message_purpose: MessagePurpose) -> bool {
+        matches!(
+            message_purpose,
+            MessagePurpose::Proposal
+                | MessagePurpose::DAC
+                | MessagePurpose::VidDisperse
+                | MessagePurpose::Upgrade
+        )
+    }
+
+    /// Size in bytes of a response
+    fn response_size(response: &[Vec<u8>]) -> usize {
+        response.iter().map(Vec::len).sum()
+    }
+
+    /// Look up the cached response for `view_number` and `message_purpose`
+    fn get(&mut self, view_number: u64, message_purpose: MessagePurpose) -> Option<Vec<Vec<u8>>> {
+        self.entries.get(&(view_number, message_purpose)).cloned()
+    }
+
+    /// Cache a response, evicting old entries to stay within the size bounds.
+    /// Empty responses, and responses that are bigger than the whole cache, are not cached.
+    fn insert(
+        &mut self,
+        view_number: u64,
+        message_purpose: MessagePurpose,
+        response: Vec<Vec<u8>>,
+    ) {
+        let size = Self::response_size(&response);
+        if response.is_empty() || size > self.max_bytes {
+            return;
+        }
+
+        if let Some((_, evicted)) = self.entries.push((view_number, message_purpose), response) {
+            self.bytes -= Self::response_size(&evicted);
+        }
+        self.bytes += size;
+
+        while self.bytes > self.max_bytes {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.bytes -= Self::response_size(&evicted);
+        }
+    }
+}
+
 /// The web server network state
 #[derive(Clone, Debug)]
 pub struct WebServerNetwork<TYPES: NodeType> {
@@ -75,19 +159,31 @@ impl<TYPES: NodeType> WebServerNetwork<TYPES> {
         &self,
         message: SendMsg<Message<TYPES>>,
     ) -> Result<(), NetworkError> {
-        let result: Result<(), ClientError> = self
-            .inner
-            .client
-            .post(&message.get_endpoint())
-            .body_binary(&message.get_message())
-            .unwrap()
-            .send()
-            .await;
+        let request = {
+            let _broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
+            self.inner
+                .client
+                .post(&message.get_endpoint())
+                .body_binary(&message.get_message())
+                .unwrap()
+        };
+        let result: Result<(), ClientError> = request.send().await;
         // error!("POST message error for endpoint {} is {:?}", &message.get_endpoint(), result.clone());
         result.map_err(|_e| NetworkError::WebServer {
             source: WebServerNetworkError::ClientError,
         })
     }
+
+    /// Register `key` in the web server's stake table
+    async fn post_stake_table_key(&self, key: &TYPES::SignatureKey) -> Result<(), ClientError> {
+        self.inner
+            .client
+            .post(&config::post_staketable_route())
+            .body_binary(key)
+            .unwrap()
+            .send()
+            .await
+    }
 }
 
 /// `TaskChannel` is a type alias for an unbounded sender channel that sends `ConsensusIntentEvent`s.
@@ -203,6 +299,10 @@ struct Inner<TYPES: NodeType> {
     #[allow(clippy::type_complexity)]
     /// A handle on the task polling for the latest view sync certificate
     latest_view_sync_certificate_task: Arc<RwLock<Option<TaskChannel<TYPES::SignatureKey>>>>,
+    /// Responses for past views we have already fetched, so retries don't hit the web server
+    response_cache: Arc<RwLock<ResponseCache>>,
+    /// The validators we have registered in the web server's stake table
+    stake_table: RwLock<BTreeSet<TYPES::SignatureKey>>,
 }
 
 impl<TYPES: NodeType> Inner<TYPES> {
@@ -225,7 +325,11 @@ impl<TYPES: NodeType> Inner<TYPES> {
 
         *tx_index += 1;
 
-        if let Ok(deserialized_message_inner) = bincode::deserialize::<Message<TYPES>>(&tx) {
+        let deserialized = {
+            let _receive_allocations = AllocationTracker::start(HotPath::Receive);
+            bincode::deserialize::<Message<TYPES>>(&tx)
+        };
+        if let Ok(deserialized_message_inner) = deserialized {
             let deserialized_message = RecvMsg {
                 message: Some(deserialized_message_inner),
             };
@@ -253,7 +357,11 @@ impl<TYPES: NodeType> Inner<TYPES> {
         seen_view_sync_certificates: &mut LruCache<u64, ()>,
     ) -> bool {
         let poll_queue = &self.poll_queue_0_1;
-        if let Ok(deserialized_message_inner) = bincode::deserialize::<Message<TYPES>>(&message) {
+        let deserialized = {
+            let _receive_allocations = AllocationTracker::start(HotPath::Receive);
+            bincode::deserialize::<Message<TYPES>>(&message)
+        };
+        if let Ok(deserialized_message_inner) = deserialized {
             let deserialized_message = RecvMsg {
                 message: Some(deserialized_message_inner),
             };
@@ -337,6 +445,47 @@ impl<TYPES: NodeType> Inner<TYPES> {
         false
     }
 
+    /// The polling tasks for `message_purpose`, if it is polled once per view and its responses
+    /// are cached
+    fn cached_task_map(
+        &self,
+        message_purpose: MessagePurpose,
+    ) -> Option<&Arc<RwLock<TaskMap<TYPES::SignatureKey>>>> {
+        match message_purpose {
+            MessagePurpose::Proposal => Some(&self.proposal_task_map),
+            MessagePurpose::DAC => Some(&self.dac_task_map),
+            MessagePurpose::VidDisperse => Some(&self.vid_disperse_task_map),
+            _ => None,
+        }
+    }
+
+    /// Get the messages at `endpoint`, answering from the response cache when the messages
+    /// for this view and purpose can no longer change.
+    ///
+    /// Responses are only cached by `poll_web_server` once they delivered what it polls for.
+    async fn get_messages(
+        &self,
+        endpoint: &str,
+        message_purpose: MessagePurpose,
+        view_number: u64,
+    ) -> Result<Option<Vec<Vec<u8>>>, ClientError> {
+        if !ResponseCache::is_cacheable(message_purpose) {
+            return self.client.get(endpoint).send().await;
+        }
+
+        if let Some(messages) = self
+            .response_cache
+            .write()
+            .await
+            .get(view_number, message_purpose)
+        {
+            debug!("Serving view {} from the response cache", view_number);
+            return Ok(Some(messages));
+        }
+
+        self.client.get(endpoint).send().await
+    }
+
     /// Pull a web server.
     async fn poll_web_server(
         &self,
@@ -434,10 +583,12 @@ impl<TYPES: NodeType> Inner<TYPES> {
                     async_sleep(self.wait_between_polls + additional_wait).await;
                 }
             } else {
-                let possible_message: Result<Option<Vec<Vec<u8>>>, ClientError> =
-                    self.client.get(&endpoint).send().await;
+                let possible_message = self
+                    .get_messages(&endpoint, message_purpose, view_number)
+                    .await;
                 if let Ok(Some(messages)) = possible_message {
-                    for message_raw in messages {
+                    let mut delivered = false;
+                    for message_raw in &messages {
                         // This is very hacky.
                         //
                         // Fundamentally, message_raw is a serialized Option(Message<TYPES>).
@@ -475,7 +626,8 @@ impl<TYPES: NodeType> Inner<TYPES> {
                                             .await;
 
                                         if should_return {
-                                            return Ok(());
+                                            delivered = true;
+                                            break;
                                         }
                                     }
                                     Some(version) => {
@@ -498,6 +650,25 @@ impl<TYPES: NodeType> Inner<TYPES> {
                             }
                         }
                     }
+
+                    // Only cache responses which delivered what we poll for, so that a response
+                    // served from the cache always ends the poll instead of being polled again
+                    // without waiting
+                    if delivered {
+                        if ResponseCache::is_cacheable(message_purpose) {
+                            self.response_cache.write().await.insert(
+                                view_number,
+                                message_purpose,
+                                messages,
+                            );
+                        }
+                        // Forget the finished task, so that polling for this view again is
+                        // answered from the cache instead of being ignored
+                        if let Some(task_map) = self.cached_task_map(message_purpose) {
+                            task_map.write().await.remove(&view_number);
+                        }
+                        return Ok(());
+                    }
                 } else {
                     async_sleep(self.wait_between_polls).await;
                 }
@@ -634,6 +805,11 @@ impl<TYPES: NodeType + 'static> WebServerNetwork<TYPES> {
             txn_task_map: Arc::default(),
             latest_proposal_task: Arc::default(),
             latest_view_sync_certificate_task: Arc::default(),
+            response_cache: Arc::new(RwLock::new(ResponseCache::new(
+                NonZeroUsize::new(RESPONSE_CACHE_ENTRIES).unwrap(),
+                RESPONSE_CACHE_BYTES,
+            ))),
+            stake_table: RwLock::default(),
         });
 
         inner.connected.store(true, Ordering::Relaxed);
@@ -1248,6 +1424,29 @@ impl<TYPES: NodeType + 'static> ConnectedNetwork<Message<TYPES>, TYPES::Signatur
             _ => {}
         }
     }
+
+    /// The web server authenticates leaders against its stake table, so register validators
+    /// which joined. The first refresh registers every validator.
+    ///
+    /// The web server doesn't take removals, since it can't authenticate them: validators which
+    /// left stay registered, and keep their index in its stake table.
+    async fn update_peers(&self, peers: BTreeSet<TYPES::SignatureKey>) {
+        // Hold the lock while posting, so concurrent refreshes with the same peers are no-ops
+        let mut stake_table = self.inner.stake_table.write().await;
+        if peers.is_subset(&stake_table) {
+            return;
+        }
+
+        for key in peers.difference(&stake_table) {
+            if let Err(e) = self.post_stake_table_key(key).await {
+                warn!(
+                    "Failed to add {:?} to the web server stake table: {:?}",
+                    key, e
+                );
+            }
+        }
+        stake_table.extend(peers);
+    }
 }
 
 impl<TYPES: NodeType> TestableNetworkingImplementation<TYPES> for WebServerNetwork<TYPES> {
@@ -1283,3 +1482,46 @@ impl<TYPES: NodeType> TestableNetworkingImplementation<TYPES> for WebServerNetwo
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// response cache stays within its entry and byte bounds
+    #[test]
+    fn test_response_cache_bounds() {
+        let mut cache = ResponseCache::new(NonZeroUsize::new(3).unwrap(), 10);
+        cache.insert(1, MessagePurpose::Proposal, vec![vec![0; 4]]);
+        cache.insert(1, MessagePurpose::DAC, vec![vec![0; 4]]);
+        assert_eq!(cache.bytes, 8);
+
+        // Evicts the least recently used entry to stay within the byte bound
+        cache.insert(2, MessagePurpose::Proposal, vec![vec![0; 4]]);
+        assert_eq!(cache.bytes, 8);
+        assert!(cache.get(1, MessagePurpose::Proposal).is_none());
+        assert!(cache.get(1, MessagePurpose::DAC).is_some());
+
+        // Responses that could never fit, and empty responses, are not cached
+        cache.insert(3, MessagePurpose::Proposal, vec![vec![0; 11]]);
+        cache.insert(4, MessagePurpose::Proposal, Vec::new());
+        assert!(cache.get(3, MessagePurpose::Proposal).is_none());
+        assert!(cache.get(4, MessagePurpose::Proposal).is_none());
+
+        // Evicts to stay within the entry bound
+        cache.insert(5, MessagePurpose::VidDisperse, vec![vec![0; 1]]);
+        cache.insert(6, MessagePurpose::VidDisperse, vec![vec![0; 1]]);
+        assert_eq!(cache.entries.len(), 3);
+        assert!(cache.get(2, MessagePurpose::Proposal).is_none());
+        assert_eq!(cache.bytes, 6);
+    }
+
+    /// only immutable responses are cached
+    #[test]
+    fn test_response_cache_cacheable() {
+        assert!(ResponseCache::is_cacheable(MessagePurpose::Proposal));
+        assert!(ResponseCache::is_cacheable(MessagePurpose::DAC));
+        assert!(!ResponseCache::is_cacheable(MessagePurpose::Vote));
+        assert!(!ResponseCache::is_cacheable(MessagePurpose::LatestProposal));
+        assert!(!ResponseCache::is_cacheable(MessagePurpose::Data));
+    }
+}
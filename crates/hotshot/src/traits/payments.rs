@@ -0,0 +1,4 @@
+//! Abstraction over how namespaces pay for the block space they use
+pub mod l1_payments;
+
+pub use hotshot_types::traits::payments::{BuilderPayments, NoPayments};
@@ -0,0 +1,136 @@
+//! [`BuilderPayments`] implementation backed by deposits held in a fee contract on the L1
+//!
+//! Namespaces pre-fund their inclusion by depositing into the fee contract. The builder only
+//! includes a namespace while its deposit covers the charges of its undecided blocks, and submits
+//! a settlement to the contract once a block it built is decided. The charges reserved for a block
+//! are released once its view, or a later one, is decided.
+
+use async_compatibility_layer::art::async_timeout;
+use async_lock::Mutex;
+use async_trait::async_trait;
+use ethereum_types::U256;
+use hotshot_types::traits::{
+    node_implementation::NodeType,
+    payments::{BlockSpaceUsage, BuilderPayments, NamespaceId, NamespaceUsage, PaymentsError},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    time::Duration,
+};
+use tracing::warn;
+
+/// Access to the fee contract on the L1
+#[async_trait]
+pub trait L1Client: Debug + Send + Sync + 'static {
+    /// The error type returned by the L1
+    type Error: Display + Send + Sync;
+
+    /// The deposit `namespace` holds in the fee contract
+    async fn deposit(&self, namespace: NamespaceId) -> Result<U256, Self::Error>;
+
+    /// Submit a settlement debiting each namespace's deposit by its charge
+    async fn settle(&self, charges: Vec<(NamespaceId, U256)>) -> Result<(), Self::Error>;
+}
+
+/// Charges namespaces a fee per byte of block space, out of their deposits in the L1 fee contract
+#[derive(Debug)]
+pub struct L1Payments<TYPES: NodeType, C: L1Client> {
+    /// The L1 fee contract
+    client: C,
+    /// The fee charged per byte of block space
+    fee_per_byte: U256,
+    /// How long to wait for the L1 to return a deposit before leaving the namespace out
+    deposit_timeout: Duration,
+    /// Charges authorized for blocks which are not decided yet, by the view they were built for
+    reserved: Mutex<BTreeMap<TYPES::Time, BTreeMap<NamespaceId, U256>>>,
+}
+
+impl<TYPES: NodeType, C: L1Client> L1Payments<TYPES, C> {
+    /// Create a new instance charging `fee_per_byte` through the fee contract behind `client`,
+    /// leaving out namespaces whose deposit can't be read within `deposit_timeout`
+    #[must_use]
+    pub fn new(client: C, fee_per_byte: U256, deposit_timeout: Duration) -> Self {
+        Self {
+            client,
+            fee_per_byte,
+            deposit_timeout,
+            reserved: Mutex::default(),
+        }
+    }
+
+    /// The charge for the block space in `usage`
+    fn charge(&self, usage: &NamespaceUsage) -> U256 {
+        self.fee_per_byte.saturating_mul(U256::from(usage.bytes))
+    }
+}
+
+#[async_trait]
+impl<TYPES: NodeType, C: L1Client> BuilderPayments<TYPES> for L1Payments<TYPES, C> {
+    async fn authorize(
+        &self,
+        view: TYPES::Time,
+        namespace: NamespaceId,
+        usage: &NamespaceUsage,
+    ) -> bool {
+        let charge = self.charge(usage);
+        if charge.is_zero() {
+            return true;
+        }
+
+        // The block is built while the view runs, so a slow L1 can't hold it back
+        let deposit =
+            match async_timeout(self.deposit_timeout, self.client.deposit(namespace)).await {
+                Ok(Ok(deposit)) => deposit,
+                Ok(Err(e)) => {
+                    warn!("Failed to read the deposit of namespace {namespace} from the L1: {e}");
+                    return false;
+                }
+                Err(_) => {
+                    warn!("Timed out reading the deposit of namespace {namespace} from the L1");
+                    return false;
+                }
+            };
+
+        let mut reserved = self.reserved.lock().await;
+        let pending = reserved
+            .iter()
+            .filter(|(reserved_view, _)| **reserved_view != view)
+            .filter_map(|(_, charges)| charges.get(&namespace))
+            .fold(U256::zero(), |total, charge| total.saturating_add(*charge));
+        if pending.saturating_add(charge) > deposit {
+            return false;
+        }
+        reserved.entry(view).or_default().insert(namespace, charge);
+        true
+    }
+
+    async fn settle(
+        &self,
+        view: TYPES::Time,
+        usage: &BlockSpaceUsage,
+    ) -> Result<(), PaymentsError> {
+        let charges: Vec<_> = usage
+            .iter()
+            .map(|(namespace, usage)| (namespace, self.charge(usage)))
+            .filter(|(_, charge)| !charge.is_zero())
+            .collect();
+        if charges.is_empty() {
+            return Ok(());
+        }
+
+        self.client
+            .settle(charges)
+            .await
+            .map_err(|e| PaymentsError::Settlement {
+                reason: e.to_string(),
+            })
+    }
+
+    async fn release_until(&self, view: TYPES::Time) {
+        self.reserved
+            .lock()
+            .await
+            .retain(|reserved_view, _| *reserved_view > view);
+    }
+}
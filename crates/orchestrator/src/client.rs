@@ -1,6 +1,6 @@
-use std::{net::IpAddr, time::Duration};
+use std::{net::IpAddr, sync::OnceLock, time::Duration};
 
-use crate::config::NetworkConfig;
+use crate::config::{ConfigPhase, NetworkConfig, NetworkConfigError, SignedNetworkConfig};
 use async_compatibility_layer::art::async_sleep;
 use clap::Parser;
 use futures::{Future, FutureExt};
@@ -10,6 +10,7 @@ use hotshot_types::{
     PeerConfig,
 };
 use surf_disco::{error::ClientError, Client};
+use tagged_base64::TaggedBase64;
 use tide_disco::Url;
 /// Holds the client connection to the orchestrator
 pub struct OrchestratorClient {
@@ -17,6 +18,11 @@ pub struct OrchestratorClient {
     client: surf_disco::Client<ClientError>,
     /// the identity
     pub identity: String,
+    /// the pre-shared orchestrator public key, which the configs we receive must be signed with
+    orchestrator_key: TaggedBase64,
+    /// the nonce of the run the first config we received was signed for, which later configs
+    /// must be signed for too
+    run_nonce: OnceLock<[u8; 32]>,
 }
 
 // VALIDATOR
@@ -37,6 +43,10 @@ pub struct ValidatorArgs {
     /// Allows for rejoining the network on a complete state loss
     #[arg(short, long)]
     pub network_config_file: Option<String>,
+    /// The orchestrator's public key, as logged by the orchestrator on startup
+    /// Configs from the orchestrator which aren't signed with this key are rejected
+    #[arg(long, env = "ORCHESTRATOR_KEY")]
+    pub orchestrator_key: String,
 }
 
 /// arguments to run multiple validators
@@ -53,6 +63,10 @@ pub struct MultiValidatorArgs {
     /// Allows for rejoining the network on a complete state loss
     #[arg(short, long)]
     pub network_config_file: Option<String>,
+    /// The orchestrator's public key, as logged by the orchestrator on startup
+    /// Configs from the orchestrator which aren't signed with this key are rejected
+    #[arg(long, env = "ORCHESTRATOR_KEY")]
+    pub orchestrator_key: String,
 }
 
 impl ValidatorArgs {
@@ -87,17 +101,47 @@ impl ValidatorArgs {
             network_config_file: multi_args
                 .network_config_file
                 .map(|s| format!("{s}-{node_index}")),
+            orchestrator_key: multi_args.orchestrator_key,
         }
     }
 }
 
 impl OrchestratorClient {
     /// Creates the client that will connect to the orchestrator
+    /// # Panics
+    /// if the orchestrator key is not valid tagged base64
     #[must_use]
     pub fn new(args: ValidatorArgs, identity: String) -> Self {
         let client = surf_disco::Client::<ClientError>::new(args.url);
+        let orchestrator_key = TaggedBase64::parse(&args.orchestrator_key)
+            .expect("Orchestrator key is not valid tagged base64");
         // TODO ED: Add healthcheck wait here
-        OrchestratorClient { client, identity }
+        OrchestratorClient {
+            client,
+            identity,
+            orchestrator_key,
+            run_nonce: OnceLock::new(),
+        }
+    }
+
+    /// Checks that a config we received was signed by the orchestrator for `phase` of the same
+    /// run as the configs we received before, before we apply it
+    /// # Panics
+    /// if the config was not signed with the orchestrator key, or was signed for another phase or
+    /// run
+    fn verify_config<K: SignatureKey, E: ElectionConfig>(
+        &self,
+        signed_config: &SignedNetworkConfig<K>,
+        phase: ConfigPhase,
+    ) -> NetworkConfig<K, E> {
+        let config = K::try_from(&self.orchestrator_key)
+            .map_err(|_| NetworkConfigError::InvalidOrchestratorKey)
+            .and_then(|orchestrator_key| {
+                signed_config.verify(&orchestrator_key, phase, self.run_nonce.get())
+            })
+            .expect("Refusing to apply config received from the orchestrator");
+        self.run_nonce.get_or_init(|| signed_config.run_nonce());
+        config
     }
 
     /// Sends an identify message to the orchestrator and attempts to get its config
@@ -105,7 +149,8 @@ impl OrchestratorClient {
     /// Will block until both are returned
     /// # Panics
     /// if unable to convert the node index from usize into u64
-    /// (only applicable on 32 bit systems)
+    /// (only applicable on 32 bit systems),
+    /// or if the config was not signed with the orchestrator key
     #[allow(clippy::type_complexity)]
     pub async fn get_config_without_peer<K: SignatureKey, E: ElectionConfig>(
         &self,
@@ -128,7 +173,7 @@ impl OrchestratorClient {
         // get the corresponding config
         let f = |client: Client<ClientError>| {
             async move {
-                let config: Result<NetworkConfig<K, E>, ClientError> = client
+                let config: Result<SignedNetworkConfig<K>, ClientError> = client
                     .post(&format!("api/config/{node_index}"))
                     .send()
                     .await;
@@ -137,7 +182,9 @@ impl OrchestratorClient {
             .boxed()
         };
 
-        let mut config = self.wait_for_fn_from_orchestrator(f).await;
+        let signed_config = self.wait_for_fn_from_orchestrator(f).await;
+        let mut config: NetworkConfig<K, E> =
+            self.verify_config(&signed_config, ConfigPhase::BeforePeerCollection);
         config.node_index = From::<u16>::from(node_index);
 
         config
@@ -163,7 +210,7 @@ impl OrchestratorClient {
     /// And get the updated config
     /// Blocks until the orchestrator collects all peer's public keys/configs
     /// # Panics
-    /// if unable to post
+    /// if unable to post, or if the config was not signed with the orchestrator key
     pub async fn post_and_wait_all_public_keys<K: SignatureKey, E: ElectionConfig>(
         &self,
         node_index: u64,
@@ -186,11 +233,13 @@ impl OrchestratorClient {
             .await;
 
         // get the newest updated config
-        self.client
+        let signed_config: SignedNetworkConfig<K> = self
+            .client
             .get("api/config_after_peer_collected")
             .send()
             .await
-            .expect("Unable to get the updated config")
+            .expect("Unable to get the updated config");
+        self.verify_config(&signed_config, ConfigPhase::AfterPeerCollection)
     }
 
     /// Tells the orchestrator this validator is ready to start
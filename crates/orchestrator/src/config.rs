@@ -115,6 +115,24 @@ pub enum NetworkConfigError {
     /// Failed to recursively create path to NetworkConfig
     #[error("Failed to recursively create path to NetworkConfig")]
     FailedToCreatePath(std::io::Error),
+    /// Failed to sign NetworkConfig
+    #[error("Failed to sign NetworkConfig")]
+    SignError,
+    /// The orchestrator secret is not a valid encoded secret
+    #[error("The orchestrator secret is not a valid encoded secret")]
+    InvalidOrchestratorSecret,
+    /// The orchestrator key is not a valid public key
+    #[error("The orchestrator key is not a valid public key")]
+    InvalidOrchestratorKey,
+    /// NetworkConfig was not signed by the orchestrator
+    #[error("NetworkConfig was not signed by the orchestrator")]
+    InvalidSignature,
+    /// NetworkConfig was signed for a different phase of the run
+    #[error("NetworkConfig was signed for a different phase of the run")]
+    WrongPhase,
+    /// NetworkConfig was signed for a different run
+    #[error("NetworkConfig was signed for a different run")]
+    WrongRun,
 }
 
 /// a network configuration
@@ -155,6 +173,115 @@ pub struct NetworkConfig<KEY: SignatureKey, ELECTIONCONFIG: ElectionConfig> {
     pub da_web_server_config: Option<WebServerConfig>,
 }
 
+/// the phase of a run a config is handed out in, which the orchestrator's signature is bound to
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigPhase {
+    /// the config a node gets for its node index, before the peers' public keys are collected
+    BeforePeerCollection,
+    /// the config with every peer's public key, once all of them are collected
+    AfterPeerCollection,
+}
+
+impl ConfigPhase {
+    /// domain separation tag for signatures over configs of this phase
+    fn domain_tag(self) -> &'static [u8] {
+        match self {
+            ConfigPhase::BeforePeerCollection => b"hotshot-orchestrator/config/before-peers",
+            ConfigPhase::AfterPeerCollection => b"hotshot-orchestrator/config/after-peers",
+        }
+    }
+}
+
+/// a network configuration as distributed by the orchestrator, signed with the orchestrator's key
+/// so that nodes only apply configs the orchestrator actually handed out.
+///
+/// The signature also covers the phase the config was handed out in and a nonce the orchestrator
+/// picks for each run, so a config can't be replayed in another phase or another run.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(bound(deserialize = ""))]
+pub struct SignedNetworkConfig<KEY: SignatureKey> {
+    /// the phase the config was handed out in
+    phase: ConfigPhase,
+    /// the nonce of the run the config was handed out in
+    run_nonce: [u8; 32],
+    /// the serialized `NetworkConfig`
+    config: Vec<u8>,
+    /// the orchestrator's signature over the hash of `phase`, `run_nonce` and `config`
+    signature: KEY::PureAssembledSignatureType,
+}
+
+impl<KEY: SignatureKey> SignedNetworkConfig<KEY> {
+    /// Serializes `config` and signs it for `phase` of the run identified by `run_nonce` with the
+    /// orchestrator's private key.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the config cannot be serialized or signed.
+    pub fn sign<E: ElectionConfig>(
+        config: &NetworkConfig<KEY, E>,
+        phase: ConfigPhase,
+        run_nonce: [u8; 32],
+        private_key: &KEY::PrivateKey,
+    ) -> Result<Self, NetworkConfigError> {
+        let config = serde_json::to_vec(config).map_err(NetworkConfigError::SerializeError)?;
+        let signature = KEY::sign(
+            private_key,
+            Self::signed_hash(phase, &run_nonce, &config).as_bytes(),
+        )
+        .map_err(|_| NetworkConfigError::SignError)?;
+        Ok(Self {
+            phase,
+            run_nonce,
+            config,
+            signature,
+        })
+    }
+
+    /// the nonce of the run this config was handed out in
+    #[must_use]
+    pub fn run_nonce(&self) -> [u8; 32] {
+        self.run_nonce
+    }
+
+    /// Checks the signature against the pre-shared orchestrator key, and that the config was
+    /// handed out in `phase` of the run identified by `run_nonce`, and only then deserializes the
+    /// config. A `run_nonce` of `None` accepts any run, for the first config a node receives.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the config was not signed by `orchestrator_key`, if
+    /// it was signed for another phase or run, or if it cannot be deserialized.
+    pub fn verify<E: ElectionConfig>(
+        &self,
+        orchestrator_key: &KEY,
+        phase: ConfigPhase,
+        run_nonce: Option<&[u8; 32]>,
+    ) -> Result<NetworkConfig<KEY, E>, NetworkConfigError> {
+        if !orchestrator_key.validate(
+            &self.signature,
+            Self::signed_hash(self.phase, &self.run_nonce, &self.config).as_bytes(),
+        ) {
+            return Err(NetworkConfigError::InvalidSignature);
+        }
+        if self.phase != phase {
+            return Err(NetworkConfigError::WrongPhase);
+        }
+        if run_nonce.is_some_and(|run_nonce| *run_nonce != self.run_nonce) {
+            return Err(NetworkConfigError::WrongRun);
+        }
+        serde_json::from_slice(&self.config).map_err(NetworkConfigError::DeserializeError)
+    }
+
+    /// the hash the orchestrator signs: keys sign 32 bytes of data
+    fn signed_hash(phase: ConfigPhase, run_nonce: &[u8; 32], config: &[u8]) -> blake3::Hash {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(phase.domain_tag());
+        hasher.update(run_nonce);
+        hasher.update(config);
+        hasher.finalize()
+    }
+}
+
 /// the source of the network config
 pub enum NetworkConfigSource {
     /// we source the network configuration from the orchestrator
@@ -505,6 +632,9 @@ pub struct HotShotConfigFile<KEY: SignatureKey> {
     pub propose_min_round_time: Duration,
     /// The maximum amount of time a leader can wait to start a round
     pub propose_max_round_time: Duration,
+    /// Encoded payloads smaller than this many bytes are sent inline with the quorum proposal
+    #[serde(default)]
+    pub inline_payload_threshold: usize,
 }
 
 /// Holds configuration for a validator node
@@ -576,6 +706,7 @@ impl<KEY: SignatureKey, E: ElectionConfig> From<HotShotConfigFile<KEY>> for HotS
             num_bootstrap: val.num_bootstrap,
             propose_min_round_time: val.propose_min_round_time,
             propose_max_round_time: val.propose_max_round_time,
+            inline_payload_threshold: val.inline_payload_threshold,
             election_config: None,
         }
     }
@@ -625,6 +756,7 @@ impl<KEY: SignatureKey> Default for HotShotConfigFile<KEY> {
             start_delay: 1,
             propose_min_round_time: Duration::from_secs(0),
             propose_max_round_time: Duration::from_secs(10),
+            inline_payload_threshold: 0,
             num_bootstrap: 5,
         }
     }
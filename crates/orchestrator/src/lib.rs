@@ -27,12 +27,13 @@ use tide_disco::{
 
 use futures::FutureExt;
 
-use crate::config::NetworkConfig;
+use crate::config::{ConfigPhase, NetworkConfig, NetworkConfigError, SignedNetworkConfig};
 
 use libp2p::identity::{
     ed25519::{Keypair as EdKeypair, SecretKey},
     Keypair,
 };
+use tagged_base64::TaggedBase64;
 /// Generate an keypair based on a `seed` and an `index`
 /// # Panics
 /// This panics if libp2p is unable to generate a secret key from the seed
@@ -46,8 +47,44 @@ pub fn libp2p_generate_indexed_identity(seed: [u8; 32], index: u64) -> Keypair {
     <EdKeypair as From<SecretKey>>::from(sk_bytes).into()
 }
 
+/// Encodes the orchestrator's public key in the form validators take it on the command line
+#[must_use]
+pub fn encode_orchestrator_key<KEY: SignatureKey>(key: KEY) -> String {
+    let key: TaggedBase64 = key.into();
+    key.to_string()
+}
+
+/// Tag of an encoded orchestrator secret
+const ORCHESTRATOR_SECRET_TAG: &str = "ORCHSECRET";
+
+/// Generates a fresh secret for the orchestrator's key pair, encoded in the form the orchestrator
+/// loads it from a key file or the environment
+/// # Panics
+/// This panics if the secret cannot be encoded as tagged base64
+#[must_use]
+pub fn generate_orchestrator_secret() -> String {
+    let secret: [u8; 32] = rand::random();
+    TaggedBase64::new(ORCHESTRATOR_SECRET_TAG, &secret)
+        .expect("orchestrator secret tag is valid")
+        .to_string()
+}
+
+/// Derives the orchestrator's key pair from a secret made by [`generate_orchestrator_secret`]
+/// # Errors
+/// This errors if `secret` is not a valid orchestrator secret
+pub fn orchestrator_key_pair<KEY: SignatureKey>(
+    secret: &str,
+) -> Result<(KEY, KEY::PrivateKey), NetworkConfigError> {
+    let secret = TaggedBase64::parse(secret.trim())
+        .ok()
+        .filter(|secret| secret.tag() == ORCHESTRATOR_SECRET_TAG)
+        .and_then(|secret| <[u8; 32]>::try_from(secret.value()).ok())
+        .ok_or(NetworkConfigError::InvalidOrchestratorSecret)?;
+    Ok(KEY::generated_from_seed_indexed(secret, 0))
+}
+
 /// The state of the orchestrator
-#[derive(Default, Clone)]
+#[derive(Clone)]
 struct OrchestratorState<KEY: SignatureKey, ELECTION: ElectionConfig> {
     /// Tracks the latest node index we have generated a configuration for
     latest_index: u16,
@@ -66,13 +103,17 @@ struct OrchestratorState<KEY: SignatureKey, ELECTION: ElectionConfig> {
     start: bool,
     /// The total nodes that have posted they are ready to start
     pub nodes_connected: u64,
+    /// The orchestrator's private key, used to sign the configs we hand out
+    private_key: KEY::PrivateKey,
+    /// Nonce picked for this run, which the configs we hand out are signed with
+    run_nonce: [u8; 32],
 }
 
 impl<KEY: SignatureKey + 'static, ELECTION: ElectionConfig + 'static>
     OrchestratorState<KEY, ELECTION>
 {
     /// create a new [`OrchestratorState`]
-    pub fn new(network_config: NetworkConfig<KEY, ELECTION>) -> Self {
+    pub fn new(network_config: NetworkConfig<KEY, ELECTION>, private_key: KEY::PrivateKey) -> Self {
         OrchestratorState {
             latest_index: 0,
             tmp_latest_index: 0,
@@ -82,8 +123,20 @@ impl<KEY: SignatureKey + 'static, ELECTION: ElectionConfig + 'static>
             pub_posted: HashSet::new(),
             nodes_connected: 0,
             start: false,
+            private_key,
+            run_nonce: rand::random(),
         }
     }
+
+    /// sign the current network config for `phase` of this run with the orchestrator's key
+    fn signed_config(&self, phase: ConfigPhase) -> Result<SignedNetworkConfig<KEY>, ServerError> {
+        SignedNetworkConfig::sign(&self.config, phase, self.run_nonce, &self.private_key).map_err(
+            |e| ServerError {
+                status: tide_disco::StatusCode::InternalServerError,
+                message: e.to_string(),
+            },
+        )
+    }
 }
 
 /// An api exposed by the orchestrator
@@ -95,10 +148,8 @@ pub trait OrchestratorApi<KEY: SignatureKey, ELECTION: ElectionConfig> {
     /// post endpoint for each node's config
     /// # Errors
     /// if unable to serve
-    fn post_getconfig(
-        &mut self,
-        _node_index: u16,
-    ) -> Result<NetworkConfig<KEY, ELECTION>, ServerError>;
+    fn post_getconfig(&mut self, _node_index: u16)
+        -> Result<SignedNetworkConfig<KEY>, ServerError>;
     /// get endpoint for the next available temporary node index
     /// # Errors
     /// if unable to serve
@@ -118,7 +169,7 @@ pub trait OrchestratorApi<KEY: SignatureKey, ELECTION: ElectionConfig> {
     /// get endpoint for the network config after all peers public keys are collected
     /// # Errors
     /// if unable to serve
-    fn get_config_after_peer_collected(&self) -> Result<NetworkConfig<KEY, ELECTION>, ServerError>;
+    fn get_config_after_peer_collected(&self) -> Result<SignedNetworkConfig<KEY>, ServerError>;
     /// get endpoint for whether or not the run has started
     /// # Errors
     /// if unable to serve
@@ -178,7 +229,7 @@ where
     fn post_getconfig(
         &mut self,
         _node_index: u16,
-    ) -> Result<NetworkConfig<KEY, ELECTION>, ServerError> {
+    ) -> Result<SignedNetworkConfig<KEY>, ServerError> {
         if self.config.libp2p_config.is_some() {
             let libp2p_config = self.config.clone().libp2p_config.unwrap();
             if libp2p_config.bootstrap_nodes.len() < libp2p_config.num_bootstrap_nodes {
@@ -188,7 +239,7 @@ where
                 });
             }
         }
-        Ok(self.config.clone())
+        self.signed_config(ConfigPhase::BeforePeerCollection)
     }
 
     // Assumes one node do not get twice
@@ -246,14 +297,14 @@ where
         Ok(self.peer_pub_ready)
     }
 
-    fn get_config_after_peer_collected(&self) -> Result<NetworkConfig<KEY, ELECTION>, ServerError> {
+    fn get_config_after_peer_collected(&self) -> Result<SignedNetworkConfig<KEY>, ServerError> {
         if !self.peer_pub_ready {
             return Err(ServerError {
                 status: tide_disco::StatusCode::BadRequest,
                 message: "Peer's public configs are not ready".to_string(),
             });
         }
-        Ok(self.config.clone())
+        self.signed_config(ConfigPhase::AfterPeerCollection)
     }
 
     fn get_start(&self) -> Result<bool, ServerError> {
@@ -348,7 +399,7 @@ where
     Ok(api)
 }
 
-/// Runs the orchestrator
+/// Runs the orchestrator, signing the configs it hands out with `private_key`
 /// # Errors
 /// This errors if tide disco runs into an issue during serving
 /// # Panics
@@ -356,6 +407,7 @@ where
 pub async fn run_orchestrator<KEY, ELECTION>(
     network_config: NetworkConfig<KEY, ELECTION>,
     url: Url,
+    private_key: KEY::PrivateKey,
 ) -> io::Result<()>
 where
     KEY: SignatureKey + 'static + serde::Serialize,
@@ -364,8 +416,12 @@ where
     let web_api =
         define_api().map_err(|_e| io::Error::new(ErrorKind::Other, "Failed to define api"));
 
+    tracing::error!(
+        "orchestrator key is {}",
+        encode_orchestrator_key(KEY::from_private(&private_key))
+    );
     let state: RwLock<OrchestratorState<KEY, ELECTION>> =
-        RwLock::new(OrchestratorState::new(network_config));
+        RwLock::new(OrchestratorState::new(network_config, private_key));
 
     let mut app = App::<RwLock<OrchestratorState<KEY, ELECTION>>, ServerError>::with_state(state);
     app.register_module("api", web_api.unwrap())
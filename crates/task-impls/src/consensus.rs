@@ -12,20 +12,21 @@ use core::time::Duration;
 use hotshot_constants::Version;
 use hotshot_constants::LOOK_AHEAD;
 use hotshot_task::task::{Task, TaskState};
+use hotshot_utils::alloc_tracking::{AllocationTracker, HotPath};
 
 use async_broadcast::Sender;
 
 use hotshot_types::{
     consensus::{Consensus, View},
-    data::{Leaf, QuorumProposal, VidDisperse},
+    data::{Leaf, PayloadAvailability, QuorumProposal, VidDisperse},
     event::{Event, EventType},
     message::{GeneralConsensusMessage, Proposal},
     simple_certificate::{QuorumCertificate, TimeoutCertificate, UpgradeCertificate},
     simple_vote::{QuorumData, QuorumVote, TimeoutData, TimeoutVote},
     traits::{
-        block_contents::BlockHeader,
+        block_contents::{vid_commitment, BlockHeader},
         consensus_api::ConsensusApi,
-        election::Membership,
+        election::{Membership, StakeTableUpdate},
         network::{ConnectedNetwork, ConsensusIntentEvent},
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
         signature_key::SignatureKey,
@@ -55,10 +56,30 @@ pub struct CommitmentAndMetadata<PAYLOAD: BlockPayload> {
     pub commitment: VidCommitment,
     /// Metadata for the block payload
     pub metadata: <PAYLOAD as BlockPayload>::Metadata,
+    /// How the block payload will be made available to replicas
+    pub payload_availability: PayloadAvailability,
     /// Flag for if this data represents the genesis block
     pub is_genesis: bool,
 }
 
+/// The stake table updates made by the blocks of the decided `leaves`, in the order they take
+/// effect. Decided leaves are collected from the newest back, so the updates are returned oldest
+/// first.
+pub fn decided_stake_table_updates<TYPES: NodeType>(
+    consensus: &Consensus<TYPES>,
+    leaves: &[Leaf<TYPES>],
+) -> Vec<StakeTableUpdate<TYPES>> {
+    leaves
+        .iter()
+        .rev()
+        .filter_map(|leaf| {
+            consensus
+                .get_state(leaf.get_view_number())
+                .and_then(|state| state.stake_table_update())
+        })
+        .collect()
+}
+
 /// Alias for Optional type for Vote Collectors
 type VoteCollectorOption<TYPES, VOTE, CERT> = Option<VoteCollectionTaskState<TYPES, VOTE, CERT>>;
 
@@ -165,9 +186,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
             let consensus = self.consensus.read().await;
 
             // ED Need to account for the genesis DA cert
-            // No need to check vid share nor da cert for genesis
-            if proposal.justify_qc.is_genesis && proposal.view_number == TYPES::Time::new(1) {
-                info!("Proposal is genesis!");
+            // No need to check vid share nor da cert for genesis, nor for a payload carried
+            // inline, which was checked against the block header when the proposal arrived.
+            let is_genesis =
+                proposal.justify_qc.is_genesis && proposal.view_number == TYPES::Time::new(1);
+            if is_genesis || proposal.payload_availability.is_inline() {
+                if is_genesis {
+                    info!("Proposal is genesis!");
+                }
 
                 let view = TYPES::Time::new(*proposal.view_number);
                 let justify_qc = proposal.justify_qc.clone();
@@ -198,6 +224,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     block_payload: None,
                     proposer_id: self.quorum_membership.get_leader(view),
                 };
+                let vote_allocations = AllocationTracker::start(HotPath::Vote);
                 let Ok(vote) = QuorumVote::<TYPES>::create_signed_vote(
                     QuorumData {
                         leaf_commit: leaf.commit(),
@@ -209,6 +236,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     error!("Failed to sign QuorumData!");
                     return false;
                 };
+                drop(vote_allocations);
 
                 let message = GeneralConsensusMessage::<TYPES>::Vote(vote);
 
@@ -282,14 +310,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                         error!("Block payload commitment does not equal da cert payload commitment. View = {}", *view);
                         return false;
                     }
-                    if let Ok(vote) = QuorumVote::<TYPES>::create_signed_vote(
+                    let vote_allocations = AllocationTracker::start(HotPath::Vote);
+                    let vote = QuorumVote::<TYPES>::create_signed_vote(
                         QuorumData {
                             leaf_commit: leaf.commit(),
                         },
                         view,
                         &self.public_key,
                         &self.private_key,
-                    ) {
+                    );
+                    drop(vote_allocations);
+                    if let Ok(vote) = vote {
                         GeneralConsensusMessage::<TYPES>::Vote(vote)
                     } else {
                         error!("Unable to sign quorum vote!");
@@ -401,6 +432,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 .metrics
                 .current_view
                 .set(usize::try_from(self.cur_view.get_u64()).unwrap());
+            consensus.metrics.record_hot_path_allocations();
             // Do the comparison before the substraction to avoid potential overflow, since
             // `last_decided_view` may be greater than `cur_view` if the node is catching up.
             if usize::try_from(self.cur_view.get_u64()).unwrap()
@@ -504,6 +536,33 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     }
                 }
 
+                // An inline payload must be small enough to skip DA, and must match the
+                // commitment in the block header, since we vote on it without a DAC.
+                if let Some(encoded_transactions) =
+                    proposal.data.payload_availability.inline_payload()
+                {
+                    if !PayloadAvailability::is_inline_size(
+                        encoded_transactions.len(),
+                        self.api.inline_payload_threshold(),
+                    ) {
+                        error!(
+                            "Inline payload of {} bytes is too large to skip DA for view {}",
+                            encoded_transactions.len(),
+                            *view
+                        );
+                        return;
+                    }
+                    if vid_commitment(encoded_transactions, self.quorum_membership.total_nodes())
+                        != proposal.data.block_header.payload_commitment()
+                    {
+                        error!(
+                            "Inline payload does not match the block header's payload commitment for view {}",
+                            *view
+                        );
+                        return;
+                    }
+                }
+
                 // NOTE: We could update our view with a valid TC but invalid QC, but that is not what we do here
                 self.update_view(view, &event_stream).await;
 
@@ -646,6 +705,29 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     error!(?proposal.signature, "Could not verify proposal.");
                     return;
                 }
+
+                // Record an inline payload just as the DA task records a DA proposal's payload,
+                // now that the proposal is known to come from the leader.
+                if let Some(encoded_transactions) =
+                    proposal.data.payload_availability.inline_payload()
+                {
+                    consensus
+                        .saved_payloads
+                        .insert(view, encoded_transactions.clone());
+
+                    // The payload is already here, stop waiting for it through DA and VID.
+                    self.quorum_network
+                        .inject_consensus_info(ConsensusIntentEvent::CancelPollForVIDDisperse(
+                            *view,
+                        ))
+                        .await;
+                    self.quorum_network
+                        .inject_consensus_info(ConsensusIntentEvent::CancelPollForDAC(*view))
+                        .await;
+                    self.committee_network
+                        .inject_consensus_info(ConsensusIntentEvent::CancelPollForProposal(*view))
+                        .await;
+                }
                 // Create a positive vote if either liveness or safety check
                 // passes.
 
@@ -808,7 +890,20 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 }
                 #[allow(clippy::cast_precision_loss)]
                 if new_decide_reached {
+                    let stake_table_updates =
+                        decided_stake_table_updates(&consensus, &leafs_decided);
                     broadcast_event(HotShotEvent::LeafDecided(leafs_decided), &event_stream).await;
+                    for update in stake_table_updates {
+                        info!(
+                            "Decided a stake table update taking effect in view {:?}",
+                            update.first_view
+                        );
+                        broadcast_event(
+                            HotShotEvent::StakeTableUpdateDecided(update),
+                            &event_stream,
+                        )
+                        .await;
+                    }
                     let decide_sent = broadcast_event(
                         Event {
                             view_number: consensus.last_decided_view,
@@ -1191,11 +1286,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 let consensus = self.consensus.read().await;
                 consensus.metrics.number_of_timeouts.add(1);
             }
-            HotShotEvent::SendPayloadCommitmentAndMetadata(payload_commitment, metadata, view) => {
+            HotShotEvent::SendPayloadCommitmentAndMetadata(
+                payload_commitment,
+                metadata,
+                payload_availability,
+                view,
+            ) => {
                 debug!("got commit and meta {:?}", payload_commitment);
                 self.payload_commitment_and_metadata = Some(CommitmentAndMetadata {
                     commitment: payload_commitment,
                     metadata,
+                    payload_availability,
                     is_genesis: false,
                 });
                 if self.quorum_membership.get_leader(view) == self.public_key
@@ -1336,6 +1437,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
             // TODO: DA cert is sent as part of the proposal here, we should split this out so we don't have to wait for it.
             let proposal = QuorumProposal {
                 block_header,
+                payload_availability: commit_and_metadata.payload_availability.clone(),
                 view_number: leaf.view_number,
                 justify_qc: consensus.high_qc.clone(),
                 timeout_certificate: timeout_certificate.or_else(|| None),
@@ -9,7 +9,7 @@ use async_lock::RwLock;
 use hotshot_task::task::{Task, TaskState};
 use hotshot_types::{
     consensus::{Consensus, View},
-    data::DAProposal,
+    data::{DAProposal, PayloadAvailability},
     event::{Event, EventType},
     message::Proposal,
     simple_certificate::DACertificate,
@@ -272,6 +272,19 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     .inject_consensus_info(ConsensusIntentEvent::CancelPollForTransactions(*view))
                     .await;
 
+                // Inline payloads are carried by the quorum proposal itself, so the DA committee
+                // has nothing to certify for this view.
+                if PayloadAvailability::is_inline_size(
+                    encoded_transactions.len(),
+                    self.api.inline_payload_threshold(),
+                ) {
+                    debug!(
+                        "Payload for view {} is sent inline, skipping DA proposal",
+                        *view
+                    );
+                    return None;
+                }
+
                 // quick hash the encoded txns with sha256
                 let encoded_transactions_hash = Sha256::digest(&encoded_transactions);
 
@@ -2,7 +2,7 @@ use crate::view_sync::ViewSyncPhase;
 
 use either::Either;
 use hotshot_types::{
-    data::{DAProposal, Leaf, QuorumProposal, UpgradeProposal, VidDisperse},
+    data::{DAProposal, Leaf, PayloadAvailability, QuorumProposal, UpgradeProposal, VidDisperse},
     message::Proposal,
     simple_certificate::{
         DACertificate, QuorumCertificate, TimeoutCertificate, UpgradeCertificate,
@@ -12,7 +12,7 @@ use hotshot_types::{
         DAVote, QuorumVote, TimeoutVote, UpgradeVote, ViewSyncCommitVote, ViewSyncFinalizeVote,
         ViewSyncPreCommitVote,
     },
-    traits::{node_implementation::NodeType, BlockPayload},
+    traits::{election::StakeTableUpdate, node_implementation::NodeType, BlockPayload},
     vid::VidCommitment,
 };
 
@@ -92,10 +92,11 @@ pub enum HotShotEvent<TYPES: NodeType> {
     TransactionsRecv(Vec<TYPES::Transaction>),
     /// Send transactions to the network
     TransactionSend(TYPES::Transaction, TYPES::SignatureKey),
-    /// Event to send block payload commitment and metadata from DA leader to the quorum; internal event only
+    /// Event to send block payload commitment, metadata, and how the payload will be made available from DA leader to the quorum; internal event only
     SendPayloadCommitmentAndMetadata(
         VidCommitment,
         <TYPES::BlockPayload as BlockPayload>::Metadata,
+        PayloadAvailability,
         TYPES::Time,
     ),
     /// Event when the transactions task has sequenced transactions. Contains the encoded transactions, the metadata, and the view number
@@ -108,6 +109,9 @@ pub enum HotShotEvent<TYPES: NodeType> {
     BlockReady(VidDisperse<TYPES>, TYPES::Time),
     /// Event when consensus decided on a leaf
     LeafDecided(Vec<Leaf<TYPES>>),
+    /// A block changing the stake table has been decided; emitted by the consensus task; received
+    /// by the quorum network event task, which connects to the validators that joined
+    StakeTableUpdateDecided(StakeTableUpdate<TYPES>),
     /// Send VID shares to VID storage nodes; emitted by the DA leader
     ///
     /// Like [`HotShotEvent::DAProposalSend`].
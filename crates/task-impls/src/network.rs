@@ -6,6 +6,7 @@ use async_broadcast::Sender;
 use async_compatibility_layer::art::async_spawn;
 use either::Either::{self, Left, Right};
 use hotshot_constants::VERSION_0_1;
+use hotshot_utils::alloc_tracking::{AllocationTracker, HotPath};
 use std::sync::Arc;
 
 use hotshot_task::task::{Task, TaskState};
@@ -14,14 +15,14 @@ use hotshot_types::{
         CommitteeConsensusMessage, GeneralConsensusMessage, Message, MessageKind, SequencingMessage,
     },
     traits::{
-        election::Membership,
+        election::{Membership, StakeTableUpdate, ViewMemberships},
         network::{ConnectedNetwork, TransmitType, ViewMessage},
-        node_implementation::NodeType,
+        node_implementation::{ConsensusTime, NodeType},
     },
     vote::{HasViewNumber, Vote},
 };
-use tracing::error;
 use tracing::instrument;
+use tracing::{error, warn};
 
 /// quorum filter
 pub fn quorum_filter<TYPES: NodeType>(event: &HotShotEvent<TYPES>) -> bool {
@@ -33,6 +34,7 @@ pub fn quorum_filter<TYPES: NodeType>(event: &HotShotEvent<TYPES>) -> bool {
             | HotShotEvent::DACSend(_, _)
             | HotShotEvent::ViewChange(_)
             | HotShotEvent::TimeoutVoteSend(_)
+            | HotShotEvent::StakeTableUpdateDecided(_)
     )
 }
 
@@ -106,6 +108,8 @@ impl<TYPES: NodeType> NetworkMessageTaskState<TYPES> {
             let sender = message.sender;
             match message.kind {
                 MessageKind::Consensus(consensus_message) => {
+                    let receive_allocations = AllocationTracker::start(HotPath::Receive);
+
                     let event = match consensus_message.0 {
                         Either::Left(general_message) => match general_message {
                             GeneralConsensusMessage::Proposal(proposal) => {
@@ -160,6 +164,7 @@ impl<TYPES: NodeType> NetworkMessageTaskState<TYPES> {
                             }
                         },
                     };
+                    drop(receive_allocations);
                     // TODO (Keyao benchmarking) Update these event variants (similar to the
                     // `TransactionsRecv` event) so we can send one event for a vector of messages.
                     // <https://github.com/EspressoSystems/HotShot/issues/1428>
@@ -193,6 +198,11 @@ pub struct NetworkEventTaskState<
     pub view: TYPES::Time,
     /// membership for the channel
     pub membership: TYPES::Membership,
+    /// The memberships of decided stake table updates, by the view they become active in, used
+    /// only to choose the peers of `channel`. Messages are still routed with `membership`.
+    ///
+    /// Only set for one task per network, so the network is refreshed once per change.
+    pub stake_table: Option<ViewMemberships<TYPES>>,
     // TODO ED Need to add exchange so we can get the recipient key and our own key?
     /// Filter which returns false for the events that this specific network task cares about
     pub filter: fn(&HotShotEvent<TYPES>) -> bool,
@@ -209,8 +219,21 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
         event: Self::Event,
         task: &mut Task<Self>,
     ) -> Option<HotShotTaskCompleted> {
-        let membership = task.state_mut().membership.clone();
-        task.state_mut().handle_event(event, &membership).await
+        let state = task.state_mut();
+        match event {
+            HotShotEvent::ViewChange(view) => {
+                state.handle_view_change(view);
+                None
+            }
+            HotShotEvent::StakeTableUpdateDecided(update) => {
+                state.handle_stake_table_update(update).await;
+                None
+            }
+            event => {
+                let membership = state.membership.clone();
+                state.handle_event(event, &membership).await
+            }
+        }
     }
 
     fn should_shutdown(event: &Self::Event) -> bool {
@@ -231,6 +254,9 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
 {
     /// Handle the given event.
     ///
+    /// View changes and stake table updates are handled by [`Self::handle_view_change`] and
+    /// [`Self::handle_stake_table_update`] instead.
+    ///
     /// Returns the completion status.
     /// # Panics
     /// Panic sif a direct message event is received with no recipient
@@ -242,6 +268,7 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
         event: HotShotEvent<TYPES>,
         membership: &TYPES::Membership,
     ) -> Option<HotShotTaskCompleted> {
+        let broadcast_allocations = AllocationTracker::start(HotPath::Broadcast);
         let (sender, message_kind, transmit_type, recipient) = match event.clone() {
             HotShotEvent::QuorumProposalSend(proposal, sender) => (
                 sender,
@@ -351,10 +378,6 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
                 TransmitType::Direct,
                 Some(membership.get_leader(vote.get_view_number() + 1)),
             ),
-            HotShotEvent::ViewChange(view) => {
-                self.view = view;
-                return None;
-            }
             HotShotEvent::Shutdown => {
                 error!("Networking task shutting down");
                 return Some(HotShotTaskCompleted);
@@ -372,6 +395,7 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
         let view = message.kind.get_view_number();
         let committee = membership.get_committee(view);
         let net = self.channel.clone();
+        drop(broadcast_allocations);
         async_spawn(async move {
             let transmit_result = match transmit_type {
                 TransmitType::Direct => net.direct_message(message, recipient.unwrap()).await,
@@ -389,4 +413,47 @@ impl<TYPES: NodeType, COMMCHANNEL: ConnectedNetwork<Message<TYPES>, TYPES::Signa
 
         None
     }
+
+    /// Move to `view`, and drop the stake table memberships no longer active, if this task
+    /// tracks the stake table.
+    pub fn handle_view_change(&mut self, view: TYPES::Time) {
+        self.view = view;
+        let Some(stake_table) = self.stake_table.as_mut() else {
+            return;
+        };
+        // Keep the membership for the previous view around, since messages for it may still be
+        // in flight.
+        stake_table.collect_garbage(TYPES::Time::new(view.saturating_sub(1)));
+    }
+
+    /// Record the membership of a decided stake table update, if this task tracks the stake
+    /// table.
+    ///
+    /// Updates must take effect after the current view, since messages for the views up to it
+    /// may already be in flight, and after every update recorded before them. Other updates are
+    /// ignored.
+    pub async fn handle_stake_table_update(&mut self, update: StakeTableUpdate<TYPES>) {
+        let Some(stake_table) = self.stake_table.as_mut() else {
+            return;
+        };
+        if update.first_view <= self.view {
+            warn!(
+                "Ignoring stake table update taking effect in view {}, which is not after the current view {}",
+                *update.first_view, *self.view
+            );
+            return;
+        }
+        if !stake_table.insert(update.first_view, update.membership) {
+            warn!(
+                "Ignoring stake table update taking effect in view {}, which is not after the last recorded update",
+                *update.first_view
+            );
+            return;
+        }
+        // Connect to validators which joined before their membership becomes active. Validators
+        // which left stay connected, since consensus keeps its startup membership.
+        self.channel
+            .update_peers(stake_table.members_from(self.view))
+            .await;
+    }
 }
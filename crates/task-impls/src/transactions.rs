@@ -4,37 +4,56 @@ use crate::{
 };
 use async_broadcast::Sender;
 use async_compatibility_layer::{
-    art::async_timeout,
+    art::{async_sleep, async_spawn, async_timeout},
     async_primitives::subscribable_rwlock::{ReadView, SubscribableRwLock},
 };
 use async_lock::RwLock;
 use bincode::config::Options;
 use commit::{Commitment, Committable};
+use futures::future::join_all;
 
 use hotshot_task::task::{Task, TaskState};
 use hotshot_types::{
-    consensus::Consensus,
+    consensus::{Consensus, ConsensusMetricsValue},
+    data::Leaf,
     event::{Event, EventType},
     traits::{
-        block_contents::BlockHeader,
+        block_contents::{BlockHeader, Transaction},
         consensus_api::ConsensusApi,
         election::Membership,
         node_implementation::{NodeImplementation, NodeType},
+        payments::{BlockSpaceUsage, BuilderPayments},
         signature_key::SignatureKey,
         BlockPayload,
     },
+    vid::VidCommitment,
 };
 use hotshot_utils::bincode::bincode_opts;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tracing::{debug, error, instrument, warn};
 
 /// A type alias for `HashMap<Commitment<T>, T>`
 type CommitmentMap<T> = HashMap<Commitment<T>, T>;
 
+/// How many times settling the block space of a decided block is attempted before giving up
+const SETTLEMENT_ATTEMPTS: u32 = 3;
+
+/// How long to wait before attempting a failed settlement again
+const SETTLEMENT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Block space used in a block we built which is not decided yet
+#[derive(Clone, Debug)]
+pub struct PendingUsage {
+    /// The payload commitment of the block, once the VID task has computed it
+    pub payload_commitment: Option<VidCommitment>,
+    /// Block space used by each namespace in the block
+    pub usage: BlockSpaceUsage,
+}
+
 /// Tracks state of a Transaction task
 pub struct TransactionTaskState<
     TYPES: NodeType,
@@ -62,6 +81,13 @@ pub struct TransactionTaskState<
     /// Membership for teh quorum
     pub membership: Arc<TYPES::Membership>,
 
+    /// Payments for block space, consulted on which namespaces to include
+    pub payments: Arc<dyn BuilderPayments<TYPES>>,
+
+    /// Block space used in the blocks we built which are not decided yet, by the view they were
+    /// built for. Empty if block space is free.
+    pub pending_usage: BTreeMap<TYPES::Time, PendingUsage>,
+
     /// This Nodes Public Key
     pub public_key: TYPES::SignatureKey,
     /// Our Private Key
@@ -126,7 +152,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 let mut included_txns = HashSet::new();
                 let mut included_txn_size = 0;
                 let mut included_txn_count = 0;
-                for leaf in leaf_chain {
+                for leaf in &leaf_chain {
                     if let Some(ref payload) = leaf.block_payload {
                         for txn in
                             payload.transaction_commitments(leaf.get_block_header().metadata())
@@ -135,6 +161,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                         }
                     }
                 }
+                self.settle_decided_blocks(&leaf_chain).await;
                 let consensus = self.consensus.read().await;
                 let txns = self.transactions.cloned().await;
 
@@ -193,7 +220,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 }
                 // TODO (Keyao) Determine whether to allow empty blocks.
                 // <https://github.com/EspressoSystems/HotShot/issues/1822>
+                let block_view = if make_block { view } else { view + 1 };
                 let txns = self.wait_for_transactions().await?;
+                let (txns, included_usage) = self.include_paying_namespaces(txns, block_view).await;
                 let (payload, metadata) =
                     match <TYPES::BlockPayload as BlockPayload>::from_transactions(txns) {
                         Ok((payload, metadata)) => (payload, metadata),
@@ -212,8 +241,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     }
                 };
 
+                if !included_usage.is_empty() {
+                    self.pending_usage.insert(
+                        block_view,
+                        PendingUsage {
+                            payload_commitment: None,
+                            usage: included_usage,
+                        },
+                    );
+                }
+
                 // send the sequenced transactions to VID and DA tasks
-                let block_view = if make_block { view } else { view + 1 };
                 broadcast_event(
                     HotShotEvent::TransactionsSequenced(encoded_transactions, metadata, block_view),
                     &event_stream,
@@ -222,6 +260,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
 
                 return None;
             }
+            HotShotEvent::SendPayloadCommitmentAndMetadata(payload_commitment, _, _, view) => {
+                // The block may be proposed in a later view than it was built for, so it is
+                // matched with the decided leaves by the payload commitment their headers carry
+                if let Some(pending) = self.pending_usage.get_mut(&view) {
+                    pending.payload_commitment = Some(payload_commitment);
+                }
+                return None;
+            }
             HotShotEvent::Shutdown => {
                 return Some(HotShotTaskCompleted);
             }
@@ -230,6 +276,88 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
         None
     }
 
+    /// Leave out the transactions of namespaces which can't pay for their block space, and return
+    /// the block space used by the rest so it can be settled once the block is decided.
+    ///
+    /// If block space is free, every transaction is included and no usage is returned.
+    async fn include_paying_namespaces(
+        &self,
+        txns: Vec<TYPES::Transaction>,
+        block_view: TYPES::Time,
+    ) -> (Vec<TYPES::Transaction>, BlockSpaceUsage) {
+        if self.payments.is_free() {
+            return (txns, BlockSpaceUsage::default());
+        }
+
+        let usage = BlockSpaceUsage::from_transactions(&txns);
+        let mut included_usage = BlockSpaceUsage::default();
+        let mut excluded_namespaces = HashSet::new();
+        // ask for every namespace at once, so slow payments delay the block only once
+        let authorizations = join_all(usage.iter().map(|(namespace, namespace_usage)| {
+            self.payments
+                .authorize(block_view, namespace, namespace_usage)
+        }))
+        .await;
+        for ((namespace, namespace_usage), authorized) in usage.iter().zip(authorizations) {
+            if authorized {
+                included_usage.insert(namespace, *namespace_usage);
+            } else {
+                warn!(
+                    "Leaving out {} transactions of namespace {} which can't pay for inclusion",
+                    namespace_usage.transactions, namespace
+                );
+                excluded_namespaces.insert(namespace);
+            }
+        }
+
+        let txns = txns
+            .into_iter()
+            .filter(|txn| !excluded_namespaces.contains(&txn.namespace()))
+            .collect();
+        (txns, included_usage)
+    }
+
+    /// Settle the block space used in the decided blocks we built, and forget about the blocks
+    /// we built which can no longer be decided.
+    async fn settle_decided_blocks(&mut self, leaf_chain: &[Leaf<TYPES>]) {
+        let Some(newest_decided_view) = leaf_chain.iter().map(Leaf::get_view_number).max() else {
+            return;
+        };
+
+        // the leaf chain is ordered from the newest leaf, settle the oldest block first
+        let mut decided_usage = Vec::new();
+        for leaf in leaf_chain.iter().rev() {
+            let payload_commitment = Some(leaf.get_block_header().payload_commitment());
+            let Some(block_view) = self
+                .pending_usage
+                .iter()
+                .find(|(_, pending)| pending.payload_commitment == payload_commitment)
+                .map(|(block_view, _)| *block_view)
+            else {
+                continue;
+            };
+            if let Some(PendingUsage { usage, .. }) = self.pending_usage.remove(&block_view) {
+                decided_usage.push((block_view, usage));
+            }
+        }
+
+        // Blocks built for views up to the decided one can no longer be decided, except for the
+        // latest one we built, which consensus still proposes if it has not proposed it yet.
+        let latest_block_view = self.pending_usage.keys().next_back().copied();
+        self.pending_usage.retain(|block_view, _| {
+            *block_view > newest_decided_view || Some(*block_view) == latest_block_view
+        });
+        self.payments.release_until(newest_decided_view).await;
+
+        if !decided_usage.is_empty() {
+            // Settling can take a round trip to the settlement layer, so don't hold up building
+            // the next block
+            let payments = Arc::clone(&self.payments);
+            let metrics = Arc::clone(&self.consensus.read().await.metrics);
+            async_spawn(settle_with_retries(payments, metrics, decided_usage));
+        }
+    }
+
     #[instrument(skip_all, fields(id = self.id, view = *self.cur_view), name = "Transaction Handling Task", level = "error")]
     async fn wait_for_transactions(&self) -> Option<Vec<TYPES::Transaction>> {
         let task_start_time = Instant::now();
@@ -309,6 +437,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
             event,
             HotShotEvent::TransactionsRecv(_)
                 | HotShotEvent::LeafDecided(_)
+                | HotShotEvent::SendPayloadCommitmentAndMetadata(_, _, _, _)
                 | HotShotEvent::Shutdown
                 | HotShotEvent::ViewChange(_)
         )
@@ -326,3 +455,37 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
         matches!(event, HotShotEvent::Shutdown)
     }
 }
+
+/// Settle the block space used in decided blocks, in order, retrying failed settlements. Blocks
+/// which still can't be settled are given up on, which is logged and counted in the metrics,
+/// since their reservations are already released and their namespaces go uncharged.
+async fn settle_with_retries<TYPES: NodeType>(
+    payments: Arc<dyn BuilderPayments<TYPES>>,
+    metrics: Arc<ConsensusMetricsValue>,
+    decided_usage: Vec<(TYPES::Time, BlockSpaceUsage)>,
+) {
+    for (block_view, usage) in decided_usage {
+        let mut attempt = 1;
+        loop {
+            match payments.settle(block_view, &usage).await {
+                Ok(()) => break,
+                Err(e) if attempt < SETTLEMENT_ATTEMPTS => {
+                    warn!(
+                        "Failed to settle block space for the block built for view {:?}, attempt {}: {:?}",
+                        block_view, attempt, e
+                    );
+                    attempt += 1;
+                    async_sleep(SETTLEMENT_RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    error!(
+                        "Giving up on settling block space for the block built for view {:?}, its namespaces were not charged: {:?}",
+                        block_view, e
+                    );
+                    metrics.number_of_failed_settlements.add(1);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -8,9 +8,10 @@ use async_std::task::spawn_blocking;
 use hotshot_task::task::{Task, TaskState};
 use hotshot_types::{
     consensus::Consensus,
-    data::VidDisperse,
+    data::{PayloadAvailability, VidDisperse},
     message::Proposal,
     traits::{
+        block_contents::vid_commitment,
         consensus_api::ConsensusApi,
         election::Membership,
         network::{ConnectedNetwork, ConsensusIntentEvent},
@@ -70,6 +71,31 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                 // get the number of quorum committee members to be used for VID calculation
                 let num_storage_nodes = self.membership.total_nodes();
 
+                // Small payloads travel inline with the quorum proposal, so we only need the
+                // commitment for the block header and there is nothing to disperse.
+                if PayloadAvailability::is_inline_size(
+                    encoded_transactions.len(),
+                    self.api.inline_payload_threshold(),
+                ) {
+                    debug!(
+                        "Payload for view {} is sent inline, skipping VID dispersal",
+                        *view_number
+                    );
+                    let payload_commitment =
+                        vid_commitment(&encoded_transactions, num_storage_nodes);
+                    broadcast_event(
+                        HotShotEvent::SendPayloadCommitmentAndMetadata(
+                            payload_commitment,
+                            metadata,
+                            PayloadAvailability::Inline(encoded_transactions),
+                            view_number,
+                        ),
+                        &event_stream,
+                    )
+                    .await;
+                    return None;
+                }
+
                 // calculate vid shares
                 let vid_disperse = spawn_blocking(move || {
                     #[allow(clippy::panic)]
@@ -85,6 +111,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, A: ConsensusApi<TYPES, I> +
                     HotShotEvent::SendPayloadCommitmentAndMetadata(
                         vid_disperse.commit,
                         metadata,
+                        PayloadAvailability::Reference,
                         view_number,
                     ),
                     &event_stream,
@@ -21,6 +21,7 @@ use hotshot_types::{
     traits::{election::Membership, node_implementation::NodeType},
     vote::{Certificate, HasViewNumber, Vote, VoteAccumulator},
 };
+use hotshot_utils::alloc_tracking::{AllocationTracker, HotPath};
 use tracing::{debug, error};
 
 /// Task state for collecting votes of one type and emiting a certificate
@@ -87,7 +88,10 @@ impl<
         let Some(ref mut accumulator) = self.accumulator else {
             return None;
         };
-        match accumulator.accumulate(vote, &self.membership) {
+        let vote_allocations = AllocationTracker::start(HotPath::Vote);
+        let accumulated = accumulator.accumulate(vote, &self.membership);
+        drop(vote_allocations);
+        match accumulated {
             Either::Left(()) => None,
             Either::Right(cert) => {
                 debug!("Certificate Formed! {:?}", cert);
@@ -11,13 +11,14 @@ use crate::test_builder::TestMetadata;
 use commit::Committable;
 use ethereum_types::U256;
 use hotshot::{
+    traits::NoPayments,
     types::{BLSPubKey, SignatureKey, SystemContextHandle},
     HotShotInitializer, Memberships, Networks, SystemContext,
 };
 use hotshot_task_impls::events::HotShotEvent;
 use hotshot_types::{
     consensus::ConsensusMetricsValue,
-    data::{Leaf, QuorumProposal, ViewNumber},
+    data::{Leaf, PayloadAvailability, QuorumProposal, ViewNumber},
     message::Proposal,
     simple_certificate::QuorumCertificate,
     simple_vote::SimpleVote,
@@ -56,8 +57,20 @@ pub async fn build_system_handle(
     Sender<HotShotEvent<TestTypes>>,
     Receiver<HotShotEvent<TestTypes>>,
 ) {
-    let builder = TestMetadata::default_multiple_rounds();
+    build_system_handle_with_metadata(TestMetadata::default_multiple_rounds(), node_id).await
+}
 
+/// create the [`SystemContextHandle`] from a node id, configured by `builder`
+/// # Panics
+/// if cannot create a [`HotShotInitializer`]
+pub async fn build_system_handle_with_metadata(
+    builder: TestMetadata,
+    node_id: u64,
+) -> (
+    SystemContextHandle<TestTypes, MemoryImpl>,
+    Sender<HotShotEvent<TestTypes>>,
+    Receiver<HotShotEvent<TestTypes>>,
+) {
     let launcher = builder.gen_launcher::<TestTypes, MemoryImpl>(node_id);
 
     let networks = (launcher.resource_generator.channel_generator)(node_id);
@@ -117,6 +130,7 @@ pub async fn build_system_handle(
         networks_bundle,
         initializer,
         ConsensusMetricsValue::default(),
+        Arc::new(NoPayments),
     )
     .await
     .expect("Could not init hotshot")
@@ -258,6 +272,7 @@ async fn build_quorum_proposal_and_signature(
         .expect("Failed to sign leaf commitment!");
     let mut proposal = QuorumProposal::<TestTypes> {
         block_header: block_header.clone(),
+        payload_availability: PayloadAvailability::Reference,
         view_number: ViewNumber::new(1),
         justify_qc: QuorumCertificate::genesis(),
         timeout_certificate: None,
@@ -317,6 +332,7 @@ async fn build_quorum_proposal_and_signature(
                 .expect("Failed to sign leaf commitment!");
         let proposal_new_view = QuorumProposal::<TestTypes> {
             block_header: block_header.clone(),
+            payload_availability: PayloadAvailability::Reference,
             view_number: ViewNumber::new(cur_view),
             justify_qc: created_qc,
             timeout_certificate: None,
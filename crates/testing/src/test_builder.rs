@@ -60,6 +60,8 @@ pub struct TestMetadata {
     pub completion_task_description: CompletionTaskDescription,
     /// Minimum transactions required for a block
     pub min_transactions: usize,
+    /// Encoded payloads smaller than this many bytes are sent inline with the quorum proposal
+    pub inline_payload_threshold: usize,
     /// timing data
     pub timing_data: TimingData,
     /// unrelabile networking metadata
@@ -178,6 +180,7 @@ impl Default for TestMetadata {
         Self {
             timing_data: TimingData::default(),
             min_transactions: 0,
+            inline_payload_threshold: 0,
             total_nodes: num_nodes,
             start_nodes: num_nodes,
             skip_late: false,
@@ -221,6 +224,7 @@ impl TestMetadata {
             total_nodes,
             num_bootstrap_nodes,
             min_transactions,
+            inline_payload_threshold,
             timing_data,
             da_committee_size,
 
@@ -262,6 +266,7 @@ impl TestMetadata {
             // TODO do we use these fields??
             propose_min_round_time: Duration::from_millis(0),
             propose_max_round_time: Duration::from_millis(1000),
+            inline_payload_threshold,
             // TODO what's the difference between this and the second config?
             election_config: Some(TYPES::Membership::default_election_config(
                 total_nodes as u64,
@@ -17,7 +17,10 @@ use futures::future::join_all;
 use hotshot::{types::SystemContextHandle, Memberships};
 use hotshot_example_types::state_types::TestInstanceState;
 
-use hotshot::{traits::TestableNodeImplementation, HotShotInitializer, SystemContext};
+use hotshot::{
+    traits::{NoPayments, TestableNodeImplementation},
+    HotShotInitializer, SystemContext,
+};
 
 use hotshot_constants::EVENT_CHANNEL_SIZE;
 use hotshot_task::task::{Task, TaskRegistry, TestTask};
@@ -429,6 +432,7 @@ where
             network_bundle,
             initializer,
             ConsensusMetricsValue::default(),
+            Arc::new(NoPayments),
         )
         .await
         .expect("Could not init hotshot")
@@ -297,3 +297,324 @@ async fn test_consensus_with_vid() {
 
     run_harness(input, output, consensus_state, false).await;
 }
+
+#[cfg(test)]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+// Checks that deciding a chain yields the stake table updates made by its blocks, oldest first.
+async fn test_consensus_decided_stake_table_updates() {
+    use hotshot_example_types::state_types::{TestInstanceState, TestValidatedState};
+    use hotshot_task_impls::consensus::decided_stake_table_updates;
+    use hotshot_testing::task_helpers::build_system_handle;
+    use hotshot_types::{consensus::View, utils::ViewInner};
+    use std::sync::Arc;
+
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let handle = build_system_handle(1).await.0;
+    let consensus_lock = handle.get_consensus();
+    let mut consensus = consensus_lock.write().await;
+
+    // Blocks in views 1 and 3 update the stake table, the block in view 2 doesn't.
+    let states = [
+        TestValidatedState::default().with_stake_table_update(5, 2),
+        TestValidatedState::default(),
+        TestValidatedState::default().with_stake_table_update(8, 3),
+    ];
+    let mut leaves = Vec::new();
+    for (view, state) in (1..).zip(states) {
+        let mut leaf = Leaf::<TestTypes>::genesis(&TestInstanceState {});
+        leaf.view_number = ViewNumber::new(view);
+        consensus.validated_state_map.insert(
+            leaf.view_number,
+            View {
+                view_inner: ViewInner::Leaf {
+                    leaf: leaf.commit(),
+                    state: Arc::new(state),
+                },
+            },
+        );
+        leaves.push(leaf);
+    }
+    // Decided leaves are collected from the newest back.
+    leaves.reverse();
+
+    let updates = decided_stake_table_updates(&consensus, &leaves);
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].first_view, ViewNumber::new(5));
+    assert_eq!(updates[0].membership.total_nodes(), 2);
+    assert_eq!(updates[1].first_view, ViewNumber::new(8));
+    assert_eq!(updates[1].membership.total_nodes(), 3);
+}
+
+/// The encoded genesis block payload, which is what the block headers of the test proposals commit
+/// to.
+fn encoded_genesis_payload() -> Vec<u8> {
+    use hotshot::traits::BlockPayload;
+    use hotshot_example_types::block_types::TestBlockPayload;
+
+    TestBlockPayload::genesis().encode().unwrap().collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+// Checks that a replica votes on a proposal carrying its payload inline without a DAC or VID share.
+async fn test_consensus_vote_inline_payload() {
+    use hotshot::tasks::{inject_consensus_polls, task_state::CreateTaskState};
+    use hotshot_task_impls::{consensus::ConsensusTaskState, harness::run_harness};
+    use hotshot_testing::{
+        task_helpers::build_system_handle_with_metadata, test_builder::TestMetadata,
+    };
+    use hotshot_types::data::PayloadAvailability;
+
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let metadata = TestMetadata {
+        inline_payload_threshold: 1024,
+        ..TestMetadata::default_multiple_rounds()
+    };
+    let handle = build_system_handle_with_metadata(metadata, 2).await.0;
+    // In view 2, node 2 is the leader.
+    let (private_key_view2, public_key_view2) = key_pair_for_id(2);
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    let mut proposal_view2 = build_quorum_proposal(&handle, &private_key_view2, 2).await;
+    proposal_view2.data.payload_availability =
+        PayloadAvailability::Inline(encoded_genesis_payload());
+
+    // No DAC or VID share is sent for view 2, the inline payload is enough to vote.
+    input.push(HotShotEvent::QuorumProposalRecv(
+        proposal_view2.clone(),
+        public_key_view2,
+    ));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(HotShotEvent::ViewChange(ViewNumber::new(2)), 1);
+    if let GeneralConsensusMessage::Vote(vote) = build_vote(&handle, proposal_view2.data).await {
+        output.insert(HotShotEvent::QuorumVoteSend(vote.clone()), 1);
+    }
+
+    let consensus_state = ConsensusTaskState::<
+        TestTypes,
+        MemoryImpl,
+        SystemContextHandle<TestTypes, MemoryImpl>,
+    >::create_from(&handle)
+    .await;
+
+    inject_consensus_polls(&consensus_state).await;
+
+    run_harness(input, output, consensus_state, false).await;
+}
+
+#[cfg(test)]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+// Checks that a proposal whose inline payload doesn't match its block header is ignored.
+async fn test_consensus_reject_mismatched_inline_payload() {
+    use hotshot::tasks::{inject_consensus_polls, task_state::CreateTaskState};
+    use hotshot_task_impls::{consensus::ConsensusTaskState, harness::run_harness};
+    use hotshot_testing::{
+        task_helpers::build_system_handle_with_metadata, test_builder::TestMetadata,
+    };
+    use hotshot_types::data::PayloadAvailability;
+
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let metadata = TestMetadata {
+        inline_payload_threshold: 1024,
+        ..TestMetadata::default_multiple_rounds()
+    };
+    let handle = build_system_handle_with_metadata(metadata, 2).await.0;
+    let (private_key_view2, public_key_view2) = key_pair_for_id(2);
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    let mut proposal_view2 = build_quorum_proposal(&handle, &private_key_view2, 2).await;
+    proposal_view2.data.payload_availability = PayloadAvailability::Inline(vec![1, 2, 3]);
+
+    // Had the proposal been accepted we would see a view change to view 2 and a vote before the
+    // view change to view 1.
+    input.push(HotShotEvent::QuorumProposalRecv(
+        proposal_view2,
+        public_key_view2,
+    ));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(1)));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(HotShotEvent::ViewChange(ViewNumber::new(1)), 1);
+
+    let consensus_state = ConsensusTaskState::<
+        TestTypes,
+        MemoryImpl,
+        SystemContextHandle<TestTypes, MemoryImpl>,
+    >::create_from(&handle)
+    .await;
+
+    inject_consensus_polls(&consensus_state).await;
+
+    run_harness(input, output, consensus_state, false).await;
+}
+
+#[cfg(test)]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+// Checks that an inline payload is ignored when it isn't below our inline payload threshold, even
+// if it matches the block header.
+async fn test_consensus_reject_oversized_inline_payload() {
+    use hotshot::tasks::{inject_consensus_polls, task_state::CreateTaskState};
+    use hotshot_task_impls::{consensus::ConsensusTaskState, harness::run_harness};
+    use hotshot_testing::task_helpers::build_system_handle;
+    use hotshot_types::data::PayloadAvailability;
+
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    // The default threshold of zero disables inline payloads.
+    let handle = build_system_handle(2).await.0;
+    let (private_key_view2, public_key_view2) = key_pair_for_id(2);
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    let mut proposal_view2 = build_quorum_proposal(&handle, &private_key_view2, 2).await;
+    proposal_view2.data.payload_availability =
+        PayloadAvailability::Inline(encoded_genesis_payload());
+
+    input.push(HotShotEvent::QuorumProposalRecv(
+        proposal_view2,
+        public_key_view2,
+    ));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(1)));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(HotShotEvent::ViewChange(ViewNumber::new(1)), 1);
+
+    let consensus_state = ConsensusTaskState::<
+        TestTypes,
+        MemoryImpl,
+        SystemContextHandle<TestTypes, MemoryImpl>,
+    >::create_from(&handle)
+    .await;
+
+    inject_consensus_polls(&consensus_state).await;
+
+    run_harness(input, output, consensus_state, false).await;
+}
+
+#[cfg(test)]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+// Checks that a decided leaf whose payload was carried inline is filled with that payload.
+async fn test_consensus_decide_inline_payload() {
+    use hotshot::tasks::{inject_consensus_polls, task_state::CreateTaskState};
+    use hotshot_example_types::block_types::TestBlockPayload;
+    use hotshot_task_impls::{consensus::ConsensusTaskState, harness::run_harness};
+    use hotshot_testing::{
+        task_helpers::build_system_handle_with_metadata, test_builder::TestMetadata,
+    };
+    use hotshot_types::{data::PayloadAvailability, event::EventType, utils::View};
+
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let metadata = TestMetadata {
+        inline_payload_threshold: 1024,
+        ..TestMetadata::default_multiple_rounds()
+    };
+    let handle = build_system_handle_with_metadata(metadata, 2).await.0;
+    let (private_key_view2, public_key_view2) = key_pair_for_id(2);
+    let (private_key_view4, public_key_view4) = key_pair_for_id(4);
+
+    // Building the view 4 proposal stores the leaves of views 1 to 3, so that receiving it decides
+    // views 1 and 2.
+    let mut proposal_view2 = build_quorum_proposal(&handle, &private_key_view2, 2).await;
+    proposal_view2.data.payload_availability =
+        PayloadAvailability::Inline(encoded_genesis_payload());
+    let proposal_view4 = build_quorum_proposal(&handle, &private_key_view4, 4).await;
+
+    let decided_leaves = {
+        let consensus_lock = handle.get_consensus();
+        let consensus = consensus_lock.read().await;
+        [2, 1].map(|view| {
+            let Some(leaf) = consensus
+                .validated_state_map
+                .get(&ViewNumber::new(view))
+                .and_then(View::get_leaf_commitment)
+                .and_then(|leaf| consensus.saved_leaves.get(&leaf))
+            else {
+                panic!("Failed to find the leaf for view {view}.");
+            };
+            leaf.clone()
+        })
+    };
+
+    let mut events = handle.get_event_stream_known_impl();
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    input.push(HotShotEvent::QuorumProposalRecv(
+        proposal_view2.clone(),
+        public_key_view2,
+    ));
+    input.push(HotShotEvent::QuorumProposalRecv(
+        proposal_view4,
+        public_key_view4,
+    ));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(HotShotEvent::ViewChange(ViewNumber::new(2)), 1);
+    if let GeneralConsensusMessage::Vote(vote) = build_vote(&handle, proposal_view2.data).await {
+        output.insert(HotShotEvent::QuorumVoteSend(vote.clone()), 1);
+    }
+    output.insert(HotShotEvent::ViewChange(ViewNumber::new(4)), 1);
+    output.insert(HotShotEvent::LeafDecided(decided_leaves.to_vec()), 1);
+
+    let consensus_state = ConsensusTaskState::<
+        TestTypes,
+        MemoryImpl,
+        SystemContextHandle<TestTypes, MemoryImpl>,
+    >::create_from(&handle)
+    .await;
+
+    inject_consensus_polls(&consensus_state).await;
+
+    run_harness(input, output, consensus_state, false).await;
+
+    let Some(leaf_chain) =
+        std::iter::from_fn(|| events.try_recv().ok()).find_map(|event| match event.event {
+            EventType::Decide { leaf_chain, .. } => Some(leaf_chain),
+            _ => None,
+        })
+    else {
+        panic!("No decide event was sent.");
+    };
+    assert_eq!(leaf_chain[0].0.get_view_number(), ViewNumber::new(2));
+    assert_eq!(
+        leaf_chain[0].0.get_block_payload(),
+        Some(TestBlockPayload::genesis())
+    );
+    assert_eq!(leaf_chain[1].0.get_view_number(), ViewNumber::new(1));
+    assert_eq!(leaf_chain[1].0.get_block_payload(), None);
+}
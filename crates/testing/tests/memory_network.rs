@@ -30,6 +30,13 @@ use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use tracing::trace;
 
+/// Counts allocations so the networking hot paths can be checked, see
+/// `hotshot_utils::alloc_tracking`.
+#[cfg(feature = "alloc-tracking")]
+#[global_allocator]
+static ALLOCATOR: hotshot_utils::alloc_tracking::CountingAllocator =
+    hotshot_utils::alloc_tracking::CountingAllocator;
+
 #[derive(
     Copy,
     Clone,
@@ -353,3 +360,53 @@ async fn memory_network_test_in_flight_message_count() {
     assert_eq!(network1.in_flight_message_count(), Some(0));
     assert_eq!(network2.in_flight_message_count(), Some(0));
 }
+
+// Check that serializing and deserializing messages counts towards the broadcast and receive paths
+#[cfg(all(feature = "alloc-tracking", debug_assertions))]
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+#[instrument]
+async fn memory_network_tracks_allocations() {
+    use hotshot_utils::alloc_tracking::{self, take_allocations, HotPath};
+
+    setup_logging();
+    assert!(alloc_tracking::ENABLED);
+
+    let group: Arc<MasterMap<Message<Test>, <Test as NodeType>::SignatureKey>> = MasterMap::new();
+    let pub_key_1 = get_pubkey();
+    let network1 = MemoryNetwork::new(
+        pub_key_1,
+        NetworkingMetricsValue::default(),
+        group.clone(),
+        Option::None,
+    );
+    let pub_key_2 = get_pubkey();
+    let network2 = MemoryNetwork::new(
+        pub_key_2,
+        NetworkingMetricsValue::default(),
+        group,
+        Option::None,
+    );
+
+    // Start from empty counts; other tests in this binary only ever add to them
+    let _ = take_allocations(HotPath::Broadcast);
+    let _ = take_allocations(HotPath::Receive);
+
+    for sent_message in gen_messages(5, 300, pub_key_1) {
+        network1
+            .direct_message(sent_message.clone(), pub_key_2)
+            .await
+            .expect("Failed to message node");
+        let mut recv_messages = network2
+            .recv_msgs()
+            .await
+            .expect("Failed to receive message");
+        fake_message_eq(sent_message, recv_messages.pop().unwrap());
+    }
+
+    assert!(take_allocations(HotPath::Broadcast) > 0);
+    assert!(take_allocations(HotPath::Receive) > 0);
+}
@@ -3,7 +3,7 @@ use hotshot_example_types::node_types::TestTypes;
 use hotshot_task_impls::events::HotShotEvent;
 use hotshot_testing::task_helpers::{build_quorum_proposal, vid_scheme_from_view_number};
 use hotshot_types::{
-    data::{DAProposal, ViewNumber},
+    data::{DAProposal, PayloadAvailability, ViewNumber},
     traits::{consensus_api::ConsensusApi, node_implementation::ConsensusTime},
 };
 use jf_primitives::vid::VidScheme;
@@ -128,7 +128,12 @@ async fn test_network_task() {
     );
     output.insert(HotShotEvent::ViewChange(ViewNumber::new(2)), 2);
     output.insert(
-        HotShotEvent::SendPayloadCommitmentAndMetadata(payload_commitment, (), ViewNumber::new(2)),
+        HotShotEvent::SendPayloadCommitmentAndMetadata(
+            payload_commitment,
+            (),
+            PayloadAvailability::Reference,
+            ViewNumber::new(2),
+        ),
         2, // 2 occurrences: both from the VID task
     );
     output.insert(
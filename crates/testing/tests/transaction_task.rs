@@ -0,0 +1,282 @@
+use async_compatibility_layer::art::{async_sleep, async_timeout};
+use async_trait::async_trait;
+use hotshot::types::SystemContextHandle;
+use hotshot_example_types::{
+    block_types::TestTransaction,
+    node_types::{MemoryImpl, TestTypes},
+    state_types::TestInstanceState,
+};
+use hotshot_task_impls::{
+    events::HotShotEvent, harness::run_harness, transactions::TransactionTaskState,
+};
+use hotshot_testing::task_helpers::build_system_handle;
+use hotshot_types::{
+    data::{Leaf, PayloadAvailability, ViewNumber},
+    traits::{
+        block_contents::vid_commitment,
+        consensus_api::ConsensusApi,
+        election::Membership,
+        node_implementation::ConsensusTime,
+        payments::{BlockSpaceUsage, BuilderPayments, NamespaceId, NamespaceUsage, PaymentsError},
+    },
+    vid::VidCommitment,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Payments which authorize either every namespace or none, and record what they are asked to
+/// settle and release
+#[derive(Debug, Default)]
+struct RecordingPayments {
+    /// Whether namespaces are authorized
+    authorized: bool,
+    /// The views and usage settled, in order
+    settled: Mutex<Vec<(ViewNumber, BlockSpaceUsage)>>,
+    /// The views released until, in order
+    released: Mutex<Vec<ViewNumber>>,
+}
+
+#[async_trait]
+impl BuilderPayments<TestTypes> for RecordingPayments {
+    async fn authorize(
+        &self,
+        _view: ViewNumber,
+        _namespace: NamespaceId,
+        _usage: &NamespaceUsage,
+    ) -> bool {
+        self.authorized
+    }
+
+    async fn settle(&self, view: ViewNumber, usage: &BlockSpaceUsage) -> Result<(), PaymentsError> {
+        self.settled.lock().unwrap().push((view, usage.clone()));
+        Ok(())
+    }
+
+    async fn release_until(&self, view: ViewNumber) {
+        self.released.lock().unwrap().push(view);
+    }
+}
+
+impl RecordingPayments {
+    /// Wait for the transactions task to settle, which it does in the background
+    async fn wait_for_settlements(&self) -> Vec<(ViewNumber, BlockSpaceUsage)> {
+        async_timeout(Duration::from_secs(2), async {
+            loop {
+                let settled = self.settled.lock().unwrap().clone();
+                if !settled.is_empty() {
+                    return settled;
+                }
+                async_sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for decided blocks to be settled")
+    }
+}
+
+/// Build the transactions task state for node 2, which is the leader of views 2 and 12
+fn transaction_task_state(
+    handle: &SystemContextHandle<TestTypes, MemoryImpl>,
+    payments: Arc<RecordingPayments>,
+) -> TransactionTaskState<TestTypes, MemoryImpl, SystemContextHandle<TestTypes, MemoryImpl>> {
+    TransactionTaskState {
+        api: handle.clone(),
+        consensus: handle.hotshot.get_consensus(),
+        transactions: Arc::default(),
+        seen_transactions: HashSet::new(),
+        cur_view: ViewNumber::new(0),
+        network: handle.hotshot.networks.quorum_network.clone(),
+        membership: handle.hotshot.memberships.quorum_membership.clone().into(),
+        payments,
+        pending_usage: BTreeMap::new(),
+        public_key: *handle.public_key(),
+        private_key: handle.private_key().clone(),
+        id: handle.hotshot.id,
+    }
+}
+
+/// A decided leaf for `view`, whose block commits to `payload_commitment`
+fn decided_leaf(view: u64, payload_commitment: VidCommitment) -> Leaf<TestTypes> {
+    let mut leaf = Leaf::genesis(&TestInstanceState {});
+    leaf.view_number = ViewNumber::new(view);
+    leaf.block_header.payload_commitment = payload_commitment;
+    leaf.block_payload = None;
+    leaf
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn test_transaction_task_leaves_out_non_paying_namespaces() {
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let handle = build_system_handle(2).await.0;
+    let payments = Arc::new(RecordingPayments::default());
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    // In view 1, node 2 is the next leader.
+    input.push(HotShotEvent::TransactionsRecv(vec![TestTransaction(vec![
+        0,
+    ])]));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(1)));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(
+        HotShotEvent::TransactionsSequenced(
+            TestTransaction::encode(vec![]).unwrap(),
+            (),
+            ViewNumber::new(2),
+        ),
+        1,
+    );
+
+    let state = transaction_task_state(&handle, payments.clone());
+    run_harness(input, output, state, false).await;
+
+    assert!(payments.settled.lock().unwrap().is_empty());
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn test_transaction_task_settles_decided_blocks_by_commitment() {
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let handle = build_system_handle(2).await.0;
+    let payments = Arc::new(RecordingPayments {
+        authorized: true,
+        ..RecordingPayments::default()
+    });
+    let quorum_membership = handle.hotshot.memberships.quorum_membership.clone();
+
+    let transactions = vec![TestTransaction(vec![0])];
+    let encoded_transactions = TestTransaction::encode(transactions.clone()).unwrap();
+    let payload_commitment = vid_commitment(&encoded_transactions, quorum_membership.total_nodes());
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    input.push(HotShotEvent::TransactionsRecv(transactions.clone()));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(1)));
+    input.push(HotShotEvent::SendPayloadCommitmentAndMetadata(
+        payload_commitment,
+        (),
+        PayloadAvailability::Reference,
+        ViewNumber::new(2),
+    ));
+    // The block built for view 2 is proposed and decided in view 3.
+    input.push(HotShotEvent::LeafDecided(vec![decided_leaf(
+        3,
+        payload_commitment,
+    )]));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(
+        HotShotEvent::TransactionsSequenced(encoded_transactions, (), ViewNumber::new(2)),
+        1,
+    );
+
+    let state = transaction_task_state(&handle, payments.clone());
+    run_harness(input, output, state, false).await;
+
+    assert_eq!(
+        payments.wait_for_settlements().await,
+        vec![(
+            ViewNumber::new(2),
+            BlockSpaceUsage::from_transactions(&transactions)
+        )]
+    );
+    assert_eq!(*payments.released.lock().unwrap(), vec![ViewNumber::new(3)]);
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn test_transaction_task_forgets_blocks_which_can_no_longer_be_decided() {
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let handle = build_system_handle(2).await.0;
+    let payments = Arc::new(RecordingPayments {
+        authorized: true,
+        ..RecordingPayments::default()
+    });
+    let quorum_membership = handle.hotshot.memberships.quorum_membership.clone();
+
+    let transactions = vec![TestTransaction(vec![0])];
+    let encoded_transactions = TestTransaction::encode(transactions.clone()).unwrap();
+    // The commitments are only used to match the decided leaves with the blocks we built.
+    let first_commitment = vid_commitment(&vec![1], quorum_membership.total_nodes());
+    let second_commitment = vid_commitment(&vec![2], quorum_membership.total_nodes());
+    let unrelated_commitment = vid_commitment(&vec![3], quorum_membership.total_nodes());
+
+    let mut input = Vec::new();
+    let mut output = HashMap::new();
+
+    // Node 2 builds blocks for views 2 and 12.
+    input.push(HotShotEvent::TransactionsRecv(transactions.clone()));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(1)));
+    input.push(HotShotEvent::SendPayloadCommitmentAndMetadata(
+        first_commitment,
+        (),
+        PayloadAvailability::Reference,
+        ViewNumber::new(2),
+    ));
+    input.push(HotShotEvent::ViewChange(ViewNumber::new(11)));
+    input.push(HotShotEvent::SendPayloadCommitmentAndMetadata(
+        second_commitment,
+        (),
+        PayloadAvailability::Reference,
+        ViewNumber::new(12),
+    ));
+    // Deciding view 5 without the block built for view 2 means it can no longer be decided.
+    input.push(HotShotEvent::LeafDecided(vec![decided_leaf(
+        5,
+        unrelated_commitment,
+    )]));
+    // A leaf carrying the forgotten block is not settled, only the block built for view 12 is.
+    input.push(HotShotEvent::LeafDecided(vec![
+        decided_leaf(13, second_commitment),
+        decided_leaf(8, first_commitment),
+    ]));
+    input.push(HotShotEvent::Shutdown);
+
+    output.insert(
+        HotShotEvent::TransactionsSequenced(encoded_transactions.clone(), (), ViewNumber::new(2)),
+        1,
+    );
+    output.insert(
+        HotShotEvent::TransactionsSequenced(encoded_transactions, (), ViewNumber::new(12)),
+        1,
+    );
+
+    let state = transaction_task_state(&handle, payments.clone());
+    run_harness(input, output, state, false).await;
+
+    // Both decided blocks are settled in order by the same background task, so the block built
+    // for view 2 would be settled first.
+    assert_eq!(
+        payments.wait_for_settlements().await,
+        vec![(
+            ViewNumber::new(12),
+            BlockSpaceUsage::from_transactions(&transactions)
+        )]
+    );
+    assert_eq!(
+        *payments.released.lock().unwrap(),
+        vec![ViewNumber::new(5), ViewNumber::new(13)]
+    );
+}
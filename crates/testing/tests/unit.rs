@@ -1,4 +1,8 @@
 mod unit {
+    mod builder_payments;
     mod message;
+    mod orchestrator_config;
+    mod payload_availability;
     mod version;
+    mod view_memberships;
 }
@@ -0,0 +1,172 @@
+use async_compatibility_layer::art::async_sleep;
+use async_lock::Mutex;
+use async_trait::async_trait;
+use ethereum_types::U256;
+use hotshot::traits::implementations::{L1Client, L1Payments};
+use hotshot_example_types::{block_types::TestTransaction, node_types::TestTypes};
+use hotshot_types::{
+    data::ViewNumber,
+    traits::{
+        node_implementation::ConsensusTime,
+        payments::{
+            BlockSpaceUsage, BuilderPayments, NamespaceId, NamespaceUsage, DEFAULT_NAMESPACE,
+        },
+    },
+};
+use std::{collections::BTreeMap, time::Duration};
+
+/// How long the payments wait for the L1 to return a deposit in these tests
+const DEPOSIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// An L1 fee contract kept in memory.
+#[derive(Debug, Default)]
+struct MockL1 {
+    /// Deposit held by each namespace
+    deposits: BTreeMap<NamespaceId, U256>,
+    /// Every settlement submitted so far
+    settlements: Mutex<Vec<Vec<(NamespaceId, U256)>>>,
+    /// How long it takes to return a deposit
+    deposit_delay: Duration,
+}
+
+#[async_trait]
+impl L1Client for MockL1 {
+    type Error = String;
+
+    async fn deposit(&self, namespace: NamespaceId) -> Result<U256, String> {
+        async_sleep(self.deposit_delay).await;
+        Ok(self.deposits.get(&namespace).copied().unwrap_or_default())
+    }
+
+    async fn settle(&self, charges: Vec<(NamespaceId, U256)>) -> Result<(), String> {
+        self.settlements.lock().await.push(charges);
+        Ok(())
+    }
+}
+
+/// Usage of a single transaction of `bytes` bytes.
+fn usage_of(bytes: u64) -> NamespaceUsage {
+    NamespaceUsage {
+        transactions: 1,
+        bytes,
+    }
+}
+
+#[test]
+fn block_space_usage_accounts_per_namespace() {
+    let transactions = vec![TestTransaction(vec![0; 10]), TestTransaction(vec![0; 20])];
+    let usage = BlockSpaceUsage::from_transactions(&transactions);
+    let default_usage = usage.get(DEFAULT_NAMESPACE).unwrap();
+    assert_eq!(default_usage.transactions, 2);
+    assert!(default_usage.bytes >= 30);
+
+    let mut usage = BlockSpaceUsage::default();
+    usage.add(1, 100);
+    usage.add(2, 50);
+    usage.add(1, 25);
+    assert_eq!(
+        usage.iter().collect::<Vec<_>>(),
+        vec![
+            (
+                1,
+                &NamespaceUsage {
+                    transactions: 2,
+                    bytes: 125
+                }
+            ),
+            (2, &usage_of(50)),
+        ]
+    );
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn l1_payments_reserve_deposits_until_settled() {
+    let client = MockL1 {
+        deposits: BTreeMap::from([(1, U256::from(150))]),
+        ..MockL1::default()
+    };
+    let payments = L1Payments::<TestTypes, _>::new(client, U256::one(), DEPOSIT_TIMEOUT);
+
+    // The deposit covers the first block, but not a second one while the first is undecided
+    assert!(
+        payments
+            .authorize(ViewNumber::new(1), 1, &usage_of(100))
+            .await
+    );
+    assert!(
+        !payments
+            .authorize(ViewNumber::new(2), 1, &usage_of(100))
+            .await
+    );
+    // Namespaces without a deposit are never included
+    assert!(
+        !payments
+            .authorize(ViewNumber::new(2), 2, &usage_of(1))
+            .await
+    );
+
+    let mut usage = BlockSpaceUsage::default();
+    usage.insert(1, usage_of(100));
+    payments.settle(ViewNumber::new(1), &usage).await.unwrap();
+    payments.release_until(ViewNumber::new(1)).await;
+
+    // Deciding the block releases its reservation
+    assert!(
+        payments
+            .authorize(ViewNumber::new(2), 1, &usage_of(100))
+            .await
+    );
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn l1_payments_release_reservations_of_blocks_never_decided() {
+    let client = MockL1 {
+        deposits: BTreeMap::from([(1, U256::from(150))]),
+        ..MockL1::default()
+    };
+    let payments = L1Payments::<TestTypes, _>::new(client, U256::one(), DEPOSIT_TIMEOUT);
+
+    // Our block for view 1 is never decided, another leader's block for view 2 is
+    assert!(
+        payments
+            .authorize(ViewNumber::new(1), 1, &usage_of(100))
+            .await
+    );
+    payments.release_until(ViewNumber::new(2)).await;
+
+    // The stale reservation no longer holds the namespace's deposit
+    assert!(
+        payments
+            .authorize(ViewNumber::new(3), 1, &usage_of(100))
+            .await
+    );
+}
+
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+async fn l1_payments_leave_out_namespaces_when_the_l1_is_slow() {
+    let client = MockL1 {
+        deposits: BTreeMap::from([(1, U256::from(150))]),
+        deposit_delay: DEPOSIT_TIMEOUT * 10,
+        ..MockL1::default()
+    };
+    let payments = L1Payments::<TestTypes, _>::new(client, U256::one(), DEPOSIT_TIMEOUT);
+
+    // The deposit would cover the block, but it isn't returned in time
+    assert!(
+        !payments
+            .authorize(ViewNumber::new(1), 1, &usage_of(100))
+            .await
+    );
+}
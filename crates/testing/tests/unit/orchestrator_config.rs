@@ -0,0 +1,119 @@
+use hotshot_example_types::node_types::TestTypes;
+use hotshot_orchestrator::{
+    config::{ConfigPhase, NetworkConfig, NetworkConfigError, SignedNetworkConfig},
+    generate_orchestrator_secret, orchestrator_key_pair,
+};
+use hotshot_types::{
+    signature_key::BLSPubKey,
+    traits::{node_implementation::NodeType, signature_key::SignatureKey},
+};
+
+/// The network config type the orchestrator hands out in tests
+type Config = NetworkConfig<BLSPubKey, <TestTypes as NodeType>::ElectionConfigType>;
+
+/// A config for a run of `rounds` views
+fn config_with_rounds(rounds: usize) -> Config {
+    Config {
+        rounds,
+        ..Config::default()
+    }
+}
+
+#[test]
+// Checks that validators accept the configs the orchestrator signs for the expected phase and
+// run, and refuse tampered, replayed or foreign configs.
+fn signed_network_config_verification() {
+    let (orchestrator_key, private_key) =
+        orchestrator_key_pair::<BLSPubKey>(&generate_orchestrator_secret()).unwrap();
+    let run_nonce = [7u8; 32];
+    let signed = SignedNetworkConfig::sign(
+        &config_with_rounds(100),
+        ConfigPhase::BeforePeerCollection,
+        run_nonce,
+        &private_key,
+    )
+    .unwrap();
+
+    let config: Config = signed
+        .verify(&orchestrator_key, ConfigPhase::BeforePeerCollection, None)
+        .unwrap();
+    assert_eq!(config.rounds, 100);
+    assert_eq!(signed.run_nonce(), run_nonce);
+    assert!(signed
+        .verify::<<TestTypes as NodeType>::ElectionConfigType>(
+            &orchestrator_key,
+            ConfigPhase::BeforePeerCollection,
+            Some(&run_nonce),
+        )
+        .is_ok());
+
+    // A config from another phase or another run is refused.
+    assert!(matches!(
+        signed.verify::<<TestTypes as NodeType>::ElectionConfigType>(
+            &orchestrator_key,
+            ConfigPhase::AfterPeerCollection,
+            None,
+        ),
+        Err(NetworkConfigError::WrongPhase)
+    ));
+    assert!(matches!(
+        signed.verify::<<TestTypes as NodeType>::ElectionConfigType>(
+            &orchestrator_key,
+            ConfigPhase::BeforePeerCollection,
+            Some(&[8u8; 32]),
+        ),
+        Err(NetworkConfigError::WrongRun)
+    ));
+
+    // A config signed with another key is refused.
+    let (other_key, _) = BLSPubKey::generated_from_seed_indexed([1u8; 32], 0);
+    assert!(matches!(
+        signed.verify::<<TestTypes as NodeType>::ElectionConfigType>(
+            &other_key,
+            ConfigPhase::BeforePeerCollection,
+            None,
+        ),
+        Err(NetworkConfigError::InvalidSignature)
+    ));
+
+    // A config altered after signing is refused.
+    let mut encoded = bincode::serialize(&signed).unwrap();
+    let position = encoded
+        .windows(b"\"rounds\":100".len())
+        .position(|window| window == b"\"rounds\":100")
+        .unwrap();
+    encoded[position + b"\"rounds\":".len()] = b'9';
+    let tampered: SignedNetworkConfig<BLSPubKey> = bincode::deserialize(&encoded).unwrap();
+    assert!(matches!(
+        tampered.verify::<<TestTypes as NodeType>::ElectionConfigType>(
+            &orchestrator_key,
+            ConfigPhase::BeforePeerCollection,
+            None,
+        ),
+        Err(NetworkConfigError::InvalidSignature)
+    ));
+}
+
+#[test]
+// Checks that the orchestrator's key pair is derived from its own secret, and that malformed
+// secrets are refused.
+fn orchestrator_secret_derives_key_pair() {
+    let secret = generate_orchestrator_secret();
+    let (key, _) = orchestrator_key_pair::<BLSPubKey>(&secret).unwrap();
+    assert_eq!(
+        orchestrator_key_pair::<BLSPubKey>(&format!("{secret}\n"))
+            .unwrap()
+            .0,
+        key
+    );
+    assert_ne!(
+        orchestrator_key_pair::<BLSPubKey>(&generate_orchestrator_secret())
+            .unwrap()
+            .0,
+        key
+    );
+    assert!(matches!(
+        orchestrator_key_pair::<BLSPubKey>("not a secret"),
+        Err(NetworkConfigError::InvalidOrchestratorSecret)
+    ));
+}
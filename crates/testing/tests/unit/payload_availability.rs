@@ -0,0 +1,24 @@
+use hotshot_types::data::PayloadAvailability;
+
+#[test]
+// Checks that payloads below the threshold are inlined and everything else is sent by reference.
+fn payload_availability_respects_threshold() {
+    let encoded_transactions = vec![0u8; 16];
+
+    let inline = PayloadAvailability::for_payload(&encoded_transactions, 17);
+    assert!(inline.is_inline());
+    assert_eq!(inline.inline_payload(), Some(&encoded_transactions));
+
+    let reference = PayloadAvailability::for_payload(&encoded_transactions, 16);
+    assert_eq!(reference, PayloadAvailability::Reference);
+    assert_eq!(reference.inline_payload(), None);
+
+    assert!(PayloadAvailability::is_inline_size(16, 17));
+    assert!(!PayloadAvailability::is_inline_size(16, 16));
+
+    // A threshold of zero disables inline payloads, even for an empty payload.
+    assert_eq!(
+        PayloadAvailability::for_payload(&[], 0),
+        PayloadAvailability::Reference
+    );
+}
@@ -0,0 +1,97 @@
+use hotshot_example_types::node_types::TestTypes;
+use hotshot_types::{
+    data::ViewNumber,
+    traits::{
+        election::{Membership, ViewMemberships},
+        node_implementation::{ConsensusTime, NodeType},
+    },
+    ValidatorConfig,
+};
+use std::collections::BTreeSet;
+
+/// The membership type used by the test node types.
+type TestMembership = <TestTypes as NodeType>::Membership;
+
+/// Build a static committee out of the nodes with the given indices.
+fn committee_of(node_ids: std::ops::Range<u64>) -> TestMembership {
+    let peers: Vec<_> = node_ids
+        .map(|node_id| {
+            ValidatorConfig::<<TestTypes as NodeType>::SignatureKey>::generated_from_seed_indexed(
+                [0u8; 32], node_id, 1,
+            )
+            .get_public_config()
+        })
+        .collect();
+    let num_nodes = peers.len() as u64;
+    <TestMembership as Membership<TestTypes>>::create_election(
+        peers,
+        <TestMembership as Membership<TestTypes>>::default_election_config(num_nodes),
+    )
+}
+
+#[test]
+// Checks that each view resolves to the membership that was active for it, even after the
+// committee has changed.
+fn view_memberships_resolve_by_view() {
+    let old_committee = committee_of(0..4);
+    let new_committee = committee_of(4..8);
+    let mut memberships = ViewMemberships::<TestTypes>::new(old_committee.clone());
+    memberships.insert(ViewNumber::new(10), new_committee.clone());
+
+    assert_eq!(memberships.get(ViewNumber::new(0)), Some(&old_committee));
+    assert_eq!(memberships.get(ViewNumber::new(9)), Some(&old_committee));
+    assert_eq!(memberships.get(ViewNumber::new(10)), Some(&new_committee));
+    assert_eq!(memberships.get(ViewNumber::new(25)), Some(&new_committee));
+    assert_eq!(memberships.latest(), &new_committee);
+
+    // A leader looked up for a view is a member of that view's committee.
+    let old_leader = memberships
+        .get(ViewNumber::new(9))
+        .expect("view 9 is retained")
+        .get_leader(ViewNumber::new(9));
+    assert!(old_committee.has_stake(&old_leader));
+    assert!(!new_committee.has_stake(&old_leader));
+}
+
+#[test]
+// Checks that garbage collection keeps the membership active at the collected view.
+fn view_memberships_collect_garbage() {
+    let old_committee = committee_of(0..4);
+    let new_committee = committee_of(4..8);
+    let mut memberships = ViewMemberships::<TestTypes>::new(old_committee.clone());
+    memberships.insert(ViewNumber::new(10), new_committee.clone());
+
+    memberships.collect_garbage(ViewNumber::new(5));
+    assert_eq!(memberships.get(ViewNumber::new(5)), Some(&old_committee));
+
+    memberships.collect_garbage(ViewNumber::new(15));
+    assert_eq!(memberships.get(ViewNumber::new(15)), Some(&new_committee));
+    // Views older than anything retained no longer resolve to a membership.
+    assert_eq!(memberships.get(ViewNumber::new(5)), None);
+}
+
+#[test]
+// Checks that nodes target the validators of a decided stake table update ahead of its first
+// view, and drop the departed validators once the old membership is superseded.
+fn view_memberships_peers_across_stake_table_update() {
+    let old_committee = committee_of(0..4);
+    let new_committee = committee_of(2..6);
+    let mut memberships = ViewMemberships::<TestTypes>::new(old_committee.clone());
+    let old_peers = old_committee.get_committee(ViewNumber::new(0));
+    let new_peers = new_committee.get_committee(ViewNumber::new(10));
+    assert_eq!(memberships.members_from(ViewNumber::new(3)), old_peers);
+
+    assert!(memberships.insert(ViewNumber::new(10), new_committee));
+    // Memberships are recorded in the order they become active.
+    assert!(!memberships.insert(ViewNumber::new(10), committee_of(0..2)));
+    assert!(!memberships.insert(ViewNumber::new(7), committee_of(0..2)));
+    let all_peers: BTreeSet<_> = old_peers.union(&new_peers).cloned().collect();
+    assert_eq!(memberships.members_from(ViewNumber::new(5)), all_peers);
+
+    // Messages for view 9 may still be in flight in view 10, so its members stay peers.
+    assert!(!memberships.collect_garbage(ViewNumber::new(9)));
+    assert_eq!(memberships.members_from(ViewNumber::new(10)), all_peers);
+
+    assert!(memberships.collect_garbage(ViewNumber::new(10)));
+    assert_eq!(memberships.members_from(ViewNumber::new(11)), new_peers);
+}
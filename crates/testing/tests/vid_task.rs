@@ -4,7 +4,7 @@ use hotshot_task_impls::{events::HotShotEvent, vid::VIDTaskState};
 use hotshot_testing::task_helpers::{build_system_handle, vid_scheme_from_view_number};
 use hotshot_types::traits::node_implementation::{ConsensusTime, NodeType};
 use hotshot_types::{
-    data::{DAProposal, VidDisperse, ViewNumber},
+    data::{DAProposal, PayloadAvailability, VidDisperse, ViewNumber},
     traits::consensus_api::ConsensusApi,
 };
 use jf_primitives::vid::VidScheme;
@@ -89,7 +89,12 @@ async fn test_vid_task() {
     );
 
     output.insert(
-        HotShotEvent::SendPayloadCommitmentAndMetadata(payload_commitment, (), ViewNumber::new(2)),
+        HotShotEvent::SendPayloadCommitmentAndMetadata(
+            payload_commitment,
+            (),
+            PayloadAvailability::Reference,
+            ViewNumber::new(2),
+        ),
         1,
     );
     output.insert(
@@ -1,12 +1,29 @@
-use std::time::Duration;
+use std::{collections::BTreeSet, marker::PhantomData, time::Duration};
 
-use async_compatibility_layer::logging::shutdown_logging;
+use async_compatibility_layer::{
+    art::{async_sleep, async_timeout},
+    logging::shutdown_logging,
+};
+use either::Right;
+use hotshot::traits::implementations::WebServerNetwork;
+use hotshot_constants::VERSION_0_1;
 use hotshot_example_types::node_types::{TestTypes, WebImpl};
 use hotshot_testing::{
     completion_task::{CompletionTaskDescription, TimeBasedCompletionTaskDescription},
     overall_safety_task::OverallSafetyPropertiesDescription,
     test_builder::{TestMetadata, TimingData},
 };
+use hotshot_types::{
+    data::{DAProposal, ViewNumber},
+    message::{CommitteeConsensusMessage, Message, MessageKind, Proposal, SequencingMessage},
+    signature_key::BLSPubKey,
+    traits::{
+        network::{ConnectedNetwork, ConsensusIntentEvent, TestableNetworkingImplementation},
+        node_implementation::ConsensusTime,
+        signature_key::SignatureKey,
+    },
+};
+use hotshot_web_server::config::MAX_VIEWS;
 use tracing::instrument;
 
 /// Web server network test
@@ -46,3 +63,98 @@ async fn web_server_network() {
         .await;
     shutdown_logging();
 }
+
+/// A DA proposal for `view`, which the web server network posts as the proposal for the view
+fn da_proposal_message(view: u64) -> Message<TestTypes> {
+    let (public_key, private_key) = BLSPubKey::generated_from_seed_indexed([0u8; 32], 0);
+    let encoded_transactions = view.to_le_bytes().to_vec();
+    let signature = BLSPubKey::sign(&private_key, &encoded_transactions).unwrap();
+    Message {
+        version: VERSION_0_1,
+        sender: public_key,
+        kind: MessageKind::Consensus(SequencingMessage(Right(
+            CommitteeConsensusMessage::DAProposal(Proposal {
+                data: DAProposal {
+                    encoded_transactions,
+                    metadata: (),
+                    view_number: ViewNumber::new(view),
+                },
+                signature,
+                _pd: PhantomData,
+            }),
+        ))),
+    }
+}
+
+/// Wait until the network has received some messages
+async fn wait_for_messages(network: &WebServerNetwork<TestTypes>) -> Vec<Message<TestTypes>> {
+    async_timeout(Duration::from_secs(10), async {
+        loop {
+            let messages = network.recv_msgs().await.unwrap();
+            if !messages.is_empty() {
+                return messages;
+            }
+            async_sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for messages from the web server")
+}
+
+/// Polling for a view again is answered from the response cache, even once the web server no
+/// longer has the view
+#[cfg_attr(
+    async_executor_impl = "tokio",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(async_executor_impl = "async-std", async_std::test)]
+#[instrument]
+async fn web_server_network_answers_repeated_polls_from_cache() {
+    async_compatibility_layer::logging::setup_logging();
+    async_compatibility_layer::logging::setup_backtrace();
+
+    let (network, _) = <WebServerNetwork<TestTypes> as TestableNetworkingImplementation<
+        TestTypes,
+    >>::generator(1, 0, 0, 1, false, None)(0);
+    network.wait_for_ready().await;
+
+    // The web server is started in the background, so retry until it accepts the proposal
+    async_timeout(Duration::from_secs(10), async {
+        while network
+            .broadcast_message(da_proposal_message(1), BTreeSet::new())
+            .await
+            .is_err()
+        {
+            async_sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the web server to start");
+
+    network
+        .inject_consensus_info(ConsensusIntentEvent::PollForProposal(1))
+        .await;
+    assert_eq!(
+        wait_for_messages(&network).await,
+        vec![da_proposal_message(1)]
+    );
+
+    // Push view 1 out of the proposals the web server keeps
+    for view in 2..=MAX_VIEWS as u64 + 1 {
+        network
+            .broadcast_message(da_proposal_message(view), BTreeSet::new())
+            .await
+            .unwrap();
+    }
+
+    network
+        .inject_consensus_info(ConsensusIntentEvent::PollForProposal(1))
+        .await;
+    assert_eq!(
+        wait_for_messages(&network).await,
+        vec![da_proposal_message(1)]
+    );
+
+    network.shut_down().await;
+    shutdown_logging();
+}
@@ -14,6 +14,7 @@ use crate::{
     utils::Terminator,
 };
 use commit::Commitment;
+use hotshot_utils::alloc_tracking::{self, HotPath};
 
 use std::{
     collections::{BTreeMap, HashMap},
@@ -88,6 +89,17 @@ pub struct ConsensusMetricsValue {
     pub outstanding_transactions_memory_size: Box<dyn Gauge>,
     /// Number of views that timed out
     pub number_of_timeouts: Box<dyn Counter>,
+    /// Number of decided blocks whose block space could not be settled
+    pub number_of_failed_settlements: Box<dyn Counter>,
+    /// Heap allocations per view on the broadcast path, registered only when
+    /// `alloc_tracking::ENABLED`
+    pub broadcast_allocations_per_view: Option<Box<dyn Histogram>>,
+    /// Heap allocations per view on the receive path, registered only when
+    /// `alloc_tracking::ENABLED`
+    pub receive_allocations_per_view: Option<Box<dyn Histogram>>,
+    /// Heap allocations per view on the vote path, registered only when
+    /// `alloc_tracking::ENABLED`
+    pub vote_allocations_per_view: Option<Box<dyn Histogram>>,
 }
 
 /// The wrapper with a string name for the networking metrics
@@ -224,6 +236,39 @@ impl ConsensusMetricsValue {
             outstanding_transactions_memory_size: metrics
                 .create_gauge(String::from("outstanding_transactions_memory_size"), None),
             number_of_timeouts: metrics.create_counter(String::from("number_of_timeouts"), None),
+            number_of_failed_settlements: metrics
+                .create_counter(String::from("number_of_failed_settlements"), None),
+            broadcast_allocations_per_view: alloc_tracking::ENABLED.then(|| {
+                metrics.create_histogram(String::from("broadcast_allocations_per_view"), None)
+            }),
+            receive_allocations_per_view: alloc_tracking::ENABLED.then(|| {
+                metrics.create_histogram(String::from("receive_allocations_per_view"), None)
+            }),
+            vote_allocations_per_view: alloc_tracking::ENABLED
+                .then(|| metrics.create_histogram(String::from("vote_allocations_per_view"), None)),
+        }
+    }
+
+    /// Record the allocations made on each hot path since the last view change.
+    /// Does nothing unless allocation tracking is enabled.
+    ///
+    /// The counters are process-wide, so the figures are only meaningful when a single node runs
+    /// in the process; see `hotshot_utils::alloc_tracking`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn record_hot_path_allocations(&self) {
+        if !alloc_tracking::ENABLED {
+            return;
+        }
+        for path in HotPath::ALL {
+            let allocations = alloc_tracking::take_allocations(path) as f64;
+            let histogram = match path {
+                HotPath::Broadcast => &self.broadcast_allocations_per_view,
+                HotPath::Receive => &self.receive_allocations_per_view,
+                HotPath::Vote => &self.vote_allocations_per_view,
+            };
+            if let Some(histogram) = histogram {
+                histogram.add_point(allocations);
+            }
         }
     }
 }
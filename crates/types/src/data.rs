@@ -169,6 +169,67 @@ impl<TYPES: NodeType> VidDisperse<TYPES> {
     }
 }
 
+/// How the block payload of a [`QuorumProposal`] is made available to replicas.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub enum PayloadAvailability {
+    /// The encoded payload is carried in the proposal itself.
+    ///
+    /// Used for small blocks, so replicas can vote without waiting for a DAC or a VID share.
+    Inline(Vec<u8>),
+    /// Only the payload commitment in the block header is carried; the payload is made available
+    /// by the DA committee and VID dispersal for the proposal's view.
+    Reference,
+}
+
+impl Debug for PayloadAvailability {
+    /// Print only the length of an inline payload, as proposals are logged at error level.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inline(encoded_transactions) => f
+                .debug_tuple("Inline")
+                .field(&format_args!("{} bytes", encoded_transactions.len()))
+                .finish(),
+            Self::Reference => f.write_str("Reference"),
+        }
+    }
+}
+
+impl PayloadAvailability {
+    /// Choose how to make an encoded payload available: payloads smaller than `inline_threshold`
+    /// bytes are carried inline, everything else by reference.
+    ///
+    /// A threshold of `0` disables inline payloads.
+    #[must_use]
+    pub fn for_payload(encoded_transactions: &[u8], inline_threshold: usize) -> Self {
+        if Self::is_inline_size(encoded_transactions.len(), inline_threshold) {
+            Self::Inline(encoded_transactions.to_vec())
+        } else {
+            Self::Reference
+        }
+    }
+
+    /// Whether an encoded payload of `len` bytes is carried inline under `inline_threshold`.
+    #[must_use]
+    pub fn is_inline_size(len: usize, inline_threshold: usize) -> bool {
+        len < inline_threshold
+    }
+
+    /// Whether the payload is carried inline.
+    #[must_use]
+    pub fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline(_))
+    }
+
+    /// The encoded payload, if it is carried inline.
+    #[must_use]
+    pub fn inline_payload(&self) -> Option<&Vec<u8>> {
+        match self {
+            Self::Inline(encoded_transactions) => Some(encoded_transactions),
+            Self::Reference => None,
+        }
+    }
+}
+
 /// Proposal to append a block.
 #[derive(custom_debug::Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(bound(deserialize = ""))]
@@ -176,6 +237,9 @@ pub struct QuorumProposal<TYPES: NodeType> {
     /// The block header to append
     pub block_header: TYPES::BlockHeader,
 
+    /// How the payload committed to by `block_header` is made available
+    pub payload_availability: PayloadAvailability,
+
     /// CurView from leader when proposing leaf
     pub view_number: TYPES::Time,
 
@@ -173,6 +173,9 @@ pub struct HotShotConfig<KEY: SignatureKey, ELECTIONCONFIG: ElectionConfig> {
     pub propose_min_round_time: Duration,
     /// The maximum amount of time a leader can wait to start a round
     pub propose_max_round_time: Duration,
+    /// Encoded payloads smaller than this many bytes are sent inline with the quorum proposal
+    /// instead of through the DA committee; `0` disables inline payloads
+    pub inline_payload_threshold: usize,
     /// the election configuration
     pub election_config: Option<ELECTIONCONFIG>,
 }
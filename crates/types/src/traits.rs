@@ -5,6 +5,7 @@ pub mod election;
 pub mod metrics;
 pub mod network;
 pub mod node_implementation;
+pub mod payments;
 pub mod qc;
 pub mod signature_key;
 pub mod stake_table;
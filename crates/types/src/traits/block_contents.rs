@@ -5,7 +5,11 @@
 
 use crate::{
     data::Leaf,
-    traits::{node_implementation::NodeType, ValidatedState},
+    traits::{
+        node_implementation::NodeType,
+        payments::{NamespaceId, DEFAULT_NAMESPACE},
+        ValidatedState,
+    },
     utils::BuilderCommitment,
     vid::{vid_scheme, VidCommitment, VidSchemeType},
 };
@@ -24,6 +28,10 @@ use std::{
 pub trait Transaction:
     Clone + Serialize + DeserializeOwned + Debug + PartialEq + Eq + Sync + Send + Committable + Hash
 {
+    /// The namespace this transaction belongs to, which is charged for its block space
+    fn namespace(&self) -> NamespaceId {
+        DEFAULT_NAMESPACE
+    }
 }
 
 /// Abstraction over the full contents of a block
@@ -33,6 +33,9 @@ pub trait ConsensusApi<TYPES: NodeType, I: NodeImplementation<TYPES>>: Send + Sy
     /// Returns the minimum transactions that must be in a block
     fn min_transactions(&self) -> usize;
 
+    /// Encoded payloads smaller than this many bytes are carried inline in the quorum proposal.
+    fn inline_payload_threshold(&self) -> usize;
+
     /// Get a reference to the public key.
     fn public_key(&self) -> &TYPES::SignatureKey;
 
@@ -3,12 +3,17 @@
 // Needed to avoid the non-binding `let` warning.
 #![allow(clippy::let_underscore_untyped)]
 
-use super::node_implementation::NodeType;
+use super::node_implementation::{ConsensusTime, NodeType};
 
 use crate::{traits::signature_key::SignatureKey, PeerConfig};
 
 use snafu::Snafu;
-use std::{collections::BTreeSet, fmt::Debug, hash::Hash, num::NonZeroU64};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    hash::Hash,
+    num::NonZeroU64,
+};
 
 /// Error for election problems
 #[derive(Snafu, Debug)]
@@ -80,3 +85,101 @@ pub trait Membership<TYPES: NodeType>:
     /// Returns the threshold required to upgrade the network protocol
     fn upgrade_threshold(&self) -> NonZeroU64;
 }
+
+/// A change to the stake table made by a decided block, and the membership elected out of it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StakeTableUpdate<TYPES: NodeType> {
+    /// The first view in which the new membership is active
+    pub first_view: TYPES::Time,
+    /// The membership elected out of the new stake table
+    pub membership: TYPES::Membership,
+}
+
+/// The memberships of a committee over time, each tagged with the first view it is active in.
+///
+/// Used to follow decided stake table updates, so the network can connect to validators before
+/// the views they become active in.
+#[derive(Clone, Debug)]
+pub struct ViewMemberships<TYPES: NodeType> {
+    /// Memberships keyed by the first view in which they are active
+    memberships: BTreeMap<TYPES::Time, TYPES::Membership>,
+}
+
+impl<TYPES: NodeType> ViewMemberships<TYPES> {
+    /// Create a membership history starting with `membership`, active from genesis.
+    #[must_use]
+    pub fn new(membership: TYPES::Membership) -> Self {
+        let mut memberships = BTreeMap::new();
+        memberships.insert(TYPES::Time::genesis(), membership);
+        Self { memberships }
+    }
+
+    /// Record that `membership` becomes active at `first_view`.
+    ///
+    /// Memberships are recorded in the order they become active, so nothing is recorded, and
+    /// `false` returned, if `first_view` is not after the first view of the latest membership.
+    pub fn insert(&mut self, first_view: TYPES::Time, membership: TYPES::Membership) -> bool {
+        if self
+            .memberships
+            .keys()
+            .next_back()
+            .is_some_and(|latest| first_view <= *latest)
+        {
+            return false;
+        }
+        self.memberships.insert(first_view, membership);
+        true
+    }
+
+    /// The membership active for `view`, or `None` if `view` is older than anything retained
+    /// after [`ViewMemberships::collect_garbage`].
+    #[must_use]
+    pub fn get(&self, view: TYPES::Time) -> Option<&TYPES::Membership> {
+        self.memberships
+            .range(..=view)
+            .next_back()
+            .map(|(_, membership)| membership)
+    }
+
+    /// The most recently recorded membership.
+    ///
+    /// # Panics
+    /// Never: a membership history always holds at least one membership.
+    #[must_use]
+    pub fn latest(&self) -> &TYPES::Membership {
+        self.memberships
+            .values()
+            .next_back()
+            .expect("a membership history always holds at least one membership")
+    }
+
+    /// The members of every committee active in the view before `view` or later, i.e. the peers
+    /// a node should stay connected to in `view`: messages for the previous view may still be in
+    /// flight, and it should already be connected to committees which take over later.
+    #[must_use]
+    pub fn members_from(&self, view: TYPES::Time) -> BTreeSet<TYPES::SignatureKey> {
+        let previous_view = TYPES::Time::new(view.saturating_sub(1));
+        let active_from = self
+            .memberships
+            .range(..=previous_view)
+            .next_back()
+            .map_or(previous_view, |(first_view, _)| *first_view);
+        self.memberships
+            .range(active_from..)
+            .flat_map(|(first_view, membership)| membership.get_committee(view.max(*first_view)))
+            .collect()
+    }
+
+    /// Drop memberships that were superseded before `view`, keeping the one active at `view`.
+    ///
+    /// Returns whether any membership was dropped.
+    pub fn collect_garbage(&mut self, view: TYPES::Time) -> bool {
+        let Some(active_from) = self.memberships.range(..=view).next_back().map(|(v, _)| *v) else {
+            return false;
+        };
+        let retained = self.memberships.split_off(&active_from);
+        let dropped = !self.memberships.is_empty();
+        self.memberships = retained;
+        dropped
+    }
+}
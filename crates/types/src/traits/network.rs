@@ -299,6 +299,13 @@ pub trait ConnectedNetwork<M: NetworkMsg, K: SignatureKey + 'static>:
     /// blocking
     /// Ideally we would pass in the `Time` type, but that requires making the entire trait generic over NodeType
     async fn inject_consensus_info(&self, _event: ConsensusIntentEvent<K>) {}
+
+    /// Connect to the validators of a changed stake table.
+    ///
+    /// Implementations connect to validators they don't know yet. Validators missing from `peers`
+    /// stay connected, since consensus still uses its startup membership. Calling this again with
+    /// known validators does nothing.
+    async fn update_peers(&self, _peers: BTreeSet<K>) {}
 }
 
 /// Describes additional functionality needed by the test network implementation
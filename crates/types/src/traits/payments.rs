@@ -0,0 +1,153 @@
+//! Abstraction over how namespaces pay for the block space they use
+//!
+//! When `HotShot` is used as a shared sequencer, each transaction belongs to a namespace. The block
+//! builder accounts the block space each namespace consumes with [`BlockSpaceUsage`], consults a
+//! [`BuilderPayments`] implementation on which namespaces may be included, and settles the charges
+//! once the block has been decided.
+
+use super::{block_contents::Transaction, node_implementation::NodeType};
+use async_trait::async_trait;
+use bincode::Options;
+use hotshot_utils::bincode::bincode_opts;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// Identifier of a namespace sharing the sequencer
+pub type NamespaceId = u64;
+
+/// The namespace of transactions which don't specify one
+pub const DEFAULT_NAMESPACE: NamespaceId = 0;
+
+/// Block space consumed by a single namespace
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NamespaceUsage {
+    /// Number of transactions included
+    pub transactions: u64,
+    /// Serialized size of the transactions included, in bytes
+    pub bytes: u64,
+}
+
+/// Block space consumed by each namespace in a block
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSpaceUsage(BTreeMap<NamespaceId, NamespaceUsage>);
+
+impl BlockSpaceUsage {
+    /// Account the block space used by `transactions`
+    #[must_use]
+    pub fn from_transactions<T: Transaction>(transactions: &[T]) -> Self {
+        let mut usage = Self::default();
+        for transaction in transactions {
+            usage.add(
+                transaction.namespace(),
+                bincode_opts().serialized_size(transaction).unwrap_or(0),
+            );
+        }
+        usage
+    }
+
+    /// Account a single transaction of `bytes` bytes to `namespace`
+    pub fn add(&mut self, namespace: NamespaceId, bytes: u64) {
+        let usage = self.0.entry(namespace).or_default();
+        usage.transactions += 1;
+        usage.bytes += bytes;
+    }
+
+    /// Set the usage of `namespace`
+    pub fn insert(&mut self, namespace: NamespaceId, usage: NamespaceUsage) {
+        self.0.insert(namespace, usage);
+    }
+
+    /// The block space used by `namespace`, if it has any transactions in the block
+    #[must_use]
+    pub fn get(&self, namespace: NamespaceId) -> Option<&NamespaceUsage> {
+        self.0.get(&namespace)
+    }
+
+    /// Iterate over the namespaces with transactions in the block, in order
+    pub fn iter(&self) -> impl Iterator<Item = (NamespaceId, &NamespaceUsage)> {
+        self.0.iter().map(|(namespace, usage)| (*namespace, usage))
+    }
+
+    /// Whether no namespace used any block space
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Errors that can occur while settling payments for block space
+#[derive(Clone, Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum PaymentsError {
+    /// The settlement layer rejected or failed to process the charges
+    Settlement {
+        /// Why settlement failed
+        reason: String,
+    },
+}
+
+/// Decides which namespaces the block builder may include, and charges them for inclusion.
+///
+/// This trait has been constructed for object safety, so that the implementation can be chosen
+/// when the node is started.
+#[async_trait]
+pub trait BuilderPayments<TYPES: NodeType>: Debug + Send + Sync + 'static {
+    /// Whether `namespace` can be charged for `usage` in the block built for `view`.
+    /// The builder leaves out the transactions of namespaces which are not authorized.
+    async fn authorize(
+        &self,
+        view: TYPES::Time,
+        namespace: NamespaceId,
+        usage: &NamespaceUsage,
+    ) -> bool;
+
+    /// Charge each namespace for the block space it used in the block built for `view`,
+    /// now that the block has been decided.
+    ///
+    /// # Errors
+    /// If the charges cannot be settled.
+    async fn settle(&self, view: TYPES::Time, usage: &BlockSpaceUsage)
+        -> Result<(), PaymentsError>;
+
+    /// Release everything authorized for blocks built for `view` or earlier, now that `view` has
+    /// been decided. Called on every decide, whether or not this node built the decided blocks,
+    /// since blocks built for earlier views can no longer be decided.
+    async fn release_until(&self, view: TYPES::Time);
+
+    /// Whether every namespace is included for free, in which case the builder neither asks for
+    /// authorization nor keeps track of block space to settle.
+    fn is_free(&self) -> bool {
+        false
+    }
+}
+
+/// A [`BuilderPayments`] implementation which includes every namespace for free
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoPayments;
+
+#[async_trait]
+impl<TYPES: NodeType> BuilderPayments<TYPES> for NoPayments {
+    async fn authorize(
+        &self,
+        _view: TYPES::Time,
+        _namespace: NamespaceId,
+        _usage: &NamespaceUsage,
+    ) -> bool {
+        true
+    }
+
+    async fn settle(
+        &self,
+        _view: TYPES::Time,
+        _usage: &BlockSpaceUsage,
+    ) -> Result<(), PaymentsError> {
+        Ok(())
+    }
+
+    async fn release_until(&self, _view: TYPES::Time) {}
+
+    fn is_free(&self) -> bool {
+        true
+    }
+}
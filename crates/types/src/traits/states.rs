@@ -8,6 +8,7 @@ use super::block_contents::TestableBlock;
 use crate::{
     data::Leaf,
     traits::{
+        election::StakeTableUpdate,
         node_implementation::{ConsensusTime, NodeType},
         BlockPayload,
     },
@@ -65,6 +66,18 @@ pub trait ValidatedState<TYPES: NodeType>:
 
     /// Gets called to notify the persistence backend that this state has been committed
     fn on_commit(&self);
+
+    /// The change to the stake table made by the block this state was produced by, if any.
+    ///
+    /// Once the block is decided, the node's quorum network connects to validators which joined
+    /// ahead of the update's first view, without a restart. Updates taking effect no later than
+    /// the node's current view are ignored.
+    ///
+    /// Only the peers follow updates: consensus, and the network tasks routing messages for it,
+    /// keep the memberships configured at startup, so validators which left stay connected.
+    fn stake_table_update(&self) -> Option<StakeTableUpdate<TYPES>> {
+        None
+    }
 }
 
 /// extra functions required on state to be usable by hotshot-testing
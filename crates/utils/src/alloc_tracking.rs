@@ -0,0 +1,150 @@
+//! Heap allocation tracking for the consensus hot paths.
+//!
+//! With the `alloc-tracking` feature enabled, a binary can install [`CountingAllocator`] as its
+//! global allocator:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: hotshot_utils::alloc_tracking::CountingAllocator =
+//!     hotshot_utils::alloc_tracking::CountingAllocator;
+//! ```
+//!
+//! Sections of the broadcast, receive and vote paths are then wrapped in an
+//! [`AllocationTracker`]: the network tasks building messages and turning them into events, the
+//! networking implementations serializing and deserializing them, and the tasks signing and
+//! accumulating votes. Allocations made by the transports themselves, which run asynchronously
+//! on other tasks, are not counted. The number of allocations made in each path is accumulated
+//! until [`take_allocations`] is called (once per view, by the consensus task). Counting only
+//! happens in debug builds; otherwise every function in this module is a no-op.
+//!
+//! The per-path counters are process-wide and every node's consensus task drains them, so the
+//! figures are only meaningful with one node per process, as in the `validator` example
+//! binaries. With several nodes in one process (the `all` and `multi-validator` examples), the
+//! allocations of all of them are split arbitrarily between whichever nodes change view first.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether allocations are actually being counted in this build.
+pub const ENABLED: bool = cfg!(all(feature = "alloc-tracking", debug_assertions));
+
+/// The hot paths we count allocations for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HotPath {
+    /// Building and serializing messages sent out to the network
+    Broadcast,
+    /// Deserializing messages received from the network and turning them into events
+    Receive,
+    /// Signing and accumulating votes
+    Vote,
+}
+
+impl HotPath {
+    /// All hot paths, in the order their counters are stored.
+    pub const ALL: [HotPath; 3] = [HotPath::Broadcast, HotPath::Receive, HotPath::Vote];
+}
+
+/// Allocations accumulated per hot path since the last call to [`take_allocations`], shared by
+/// every node in the process.
+static PATH_ALLOCATIONS: [AtomicUsize; 3] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+/// Global allocator that counts the allocations made on each thread.
+#[cfg(feature = "alloc-tracking")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-tracking")]
+std::thread_local! {
+    /// Allocations made by the current thread. `const` initialised so that accessing it from
+    /// inside the allocator never allocates.
+    static THREAD_ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(feature = "alloc-tracking")]
+// SAFETY: every call is forwarded unchanged to the system allocator.
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        count_allocation();
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: std::alloc::Layout) -> *mut u8 {
+        count_allocation();
+        std::alloc::System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout);
+    }
+}
+
+/// Bump the allocation count of the current thread.
+#[cfg(feature = "alloc-tracking")]
+fn count_allocation() {
+    if ENABLED {
+        // The thread local may already be destroyed while the thread is shutting down.
+        let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get().wrapping_add(1)));
+    }
+}
+
+/// Number of allocations the current thread has made so far.
+fn thread_allocations() -> usize {
+    #[cfg(feature = "alloc-tracking")]
+    {
+        THREAD_ALLOCATIONS
+            .try_with(std::cell::Cell::get)
+            .unwrap_or(0)
+    }
+    #[cfg(not(feature = "alloc-tracking"))]
+    {
+        0
+    }
+}
+
+/// Counts the allocations made by the current thread while it is alive, and adds them to its
+/// hot path when dropped.
+///
+/// Counts are per thread, so a tracker must not be held across an `.await`.
+#[derive(Debug)]
+#[must_use = "allocations are only counted while the tracker is alive"]
+pub struct AllocationTracker {
+    /// The path the allocations are attributed to
+    path: HotPath,
+    /// Thread allocation count when the tracker was created
+    start: usize,
+}
+
+impl AllocationTracker {
+    /// Start counting allocations for `path`.
+    pub fn start(path: HotPath) -> Self {
+        Self {
+            path,
+            start: thread_allocations(),
+        }
+    }
+}
+
+impl Drop for AllocationTracker {
+    fn drop(&mut self) {
+        if ENABLED {
+            let allocations = thread_allocations().wrapping_sub(self.start);
+            PATH_ALLOCATIONS[self.path as usize].fetch_add(allocations, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Return the allocations accumulated for `path` since the last call, and reset its count.
+///
+/// The count covers every node in the process, not just the caller.
+#[must_use]
+pub fn take_allocations(path: HotPath) -> usize {
+    PATH_ALLOCATIONS[path as usize].swap(0, Ordering::Relaxed)
+}
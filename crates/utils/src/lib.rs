@@ -1,5 +1,8 @@
 //! Contains general utility structures and methods
 
+/// Provides allocation counting for the consensus hot paths
+pub mod alloc_tracking;
+
 /// Provides bincode options
 pub mod bincode;
 
@@ -86,7 +86,8 @@ struct WebServerState<KEY> {
 
     /// shutdown signal
     shutdown: Option<OneShotReceiver<()>>,
-    /// stake table with leader keys
+    /// stake table with leader keys, in the order they were registered.
+    /// Keys are never removed, so a key's position is its stable index.
     stake_table: Vec<KEY>,
     /// prng for generating endpoint
     _prng: StdRng,
@@ -682,18 +683,23 @@ impl<KEY: SignatureKey> WebServerDataSource<KEY> for WebServerState<KEY> {
 
     fn post_staketable(&mut self, key: Vec<u8>) -> Result<(), Error> {
         // KALEY TODO: need security checks here
-        let new_key = KEY::from_bytes(&key).map_err(|_| ServerError {
-            status: StatusCode::BadRequest,
-            message: "Only signature keys can be added to stake table".to_string(),
-        })?;
+        let new_key = parse_staketable_key::<KEY>(&key)?;
+        // Validators re-post the keys they know of whenever the stake table changes; a key
+        // which is already registered keeps its index, secret and proposals
+        if self.stake_table.contains(&new_key) {
+            return Ok(());
+        }
         let node_index = self.stake_table.len() as u64;
-        //generate secret for leader's first submission endpoint when key is added
-        let secret = thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(30)
-            .map(char::from)
-            .collect();
-        self.proposals.insert(node_index, (secret, Vec::new()));
+        //generate secret for leader's first submission endpoint when key is added,
+        //unless a proposal for that view is already stored on a live server
+        self.proposals.entry(node_index).or_insert_with(|| {
+            let secret = thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(30)
+                .map(char::from)
+                .collect();
+            (secret, Vec::new())
+        });
         self.stake_table.push(new_key);
         Ok(())
     }
@@ -727,19 +733,32 @@ impl<KEY: SignatureKey> WebServerDataSource<KEY> for WebServerState<KEY> {
             .entry(view_number)
             .and_modify(|(_, empty_proposal)| empty_proposal.append(&mut proposal));
 
-        //generate new secret for the next time this node is leader
-        let secret = thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(30)
-            .map(char::from)
-            .collect();
+        //generate new secret for the next time this node is leader, going round-robin over the
+        //stake table as it is now. Keys joining in the meantime shift the rotation, so never
+        //replace a secret or proposal already stored for that view
         let next_view_for_leader = view_number + self.stake_table.len() as u64;
         self.proposals
-            .insert(next_view_for_leader, (secret, Vec::new()));
+            .entry(next_view_for_leader)
+            .or_insert_with(|| {
+                let secret = thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(30)
+                    .map(char::from)
+                    .collect();
+                (secret, Vec::new())
+            });
         Ok(())
     }
 }
 
+/// Decode a key posted to the stake table
+fn parse_staketable_key<KEY: SignatureKey>(key: &[u8]) -> Result<KEY, Error> {
+    bincode::deserialize(key).map_err(|_| ServerError {
+        status: StatusCode::BadRequest,
+        message: "Only signature keys can be added to stake table".to_string(),
+    })
+}
+
 /// configurability options for the web server
 #[derive(Args, Default)]
 pub struct Options {
@@ -956,3 +975,52 @@ pub async fn run_web_server<KEY: SignatureKey + 'static>(
 
     app_future.await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{WebServerDataSource, WebServerState};
+    use hotshot_types::{signature_key::BLSPubKey, traits::signature_key::SignatureKey};
+
+    /// The key of node `index`, encoded as validators post it to the stake table
+    fn encoded_key(index: u64) -> Vec<u8> {
+        bincode::serialize(&BLSPubKey::generated_from_seed_indexed([0u8; 32], index).0).unwrap()
+    }
+
+    #[test]
+    // Checks that keys keep their index in the stake table, and that registering keys on a live
+    // server never replaces a stored secret or proposal.
+    fn stake_table_registration_keeps_indices_and_proposals() {
+        let mut state = WebServerState::<BLSPubKey>::new();
+        state.post_staketable(encoded_key(0)).unwrap();
+        state.post_staketable(encoded_key(1)).unwrap();
+        assert_eq!(state.stake_table.len(), 2);
+
+        // The leader of view 1 proposes, and gets a secret for view 3
+        state.post_secret_proposal(1, vec![1, 2, 3]).unwrap();
+        let proposals = state.proposals.clone();
+        assert_eq!(proposals[&1].1, vec![1, 2, 3]);
+        assert!(proposals.contains_key(&3));
+
+        // Re-registering a key is a no-op
+        state.post_staketable(encoded_key(1)).unwrap();
+        state.post_staketable(encoded_key(0)).unwrap();
+        assert_eq!(state.stake_table.len(), 2);
+        assert_eq!(state.proposals, proposals);
+
+        // New keys are appended after the existing ones, without replacing the stored secret
+        state.post_staketable(encoded_key(2)).unwrap();
+        state.post_staketable(encoded_key(3)).unwrap();
+        assert_eq!(
+            state.stake_table,
+            (0..4)
+                .map(|index| BLSPubKey::generated_from_seed_indexed([0u8; 32], index).0)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(state.proposals[&1], proposals[&1]);
+        assert_eq!(state.proposals[&3], proposals[&3]);
+
+        // Anything but a signature key is refused
+        assert!(state.post_staketable(vec![1, 2, 3]).is_err());
+        assert_eq!(state.stake_table.len(), 4);
+    }
+}